@@ -0,0 +1,268 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::hooks::ipc::{DecisionReply, DecisionRequest};
+use crate::hooks::{PermissionDecision, ToolUse};
+
+/// How long the user has to answer; the hook waits a little longer.
+pub const APPROVAL_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// Payload of the `approval-requested` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub id: u64,
+    pub session_id: String,
+    pub cwd: String,
+    pub tool_name: String,
+    pub summary: String,
+    /// The full command or file path
+    pub detail: Option<String>,
+    /// The summary leaves part of the command out, so only the terminal may approve it
+    pub truncated: bool,
+    pub reason: String,
+    pub timeout_secs: u64,
+}
+
+/// Payload of the `approval-resolved` event; no decision means it timed out.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalResolved {
+    pub id: u64,
+    pub decision: Option<PermissionDecision>,
+}
+
+struct Waiting {
+    sender: Sender<PermissionDecision>,
+    approval: ApprovalRequest,
+}
+
+struct Pending {
+    next_id: u64,
+    waiting: BTreeMap<u64, Waiting>,
+    /// The approval read out and being listened for; the others are queued
+    /// in the dictation window until it is answered
+    presented: Option<u64>,
+}
+
+// Approvals waiting for an answer, each blocking one hook connection
+static PENDING: Mutex<Pending> = Mutex::new(Pending {
+    next_id: 1,
+    waiting: BTreeMap::new(),
+    presented: None,
+});
+
+/// Shows a tool call the safety rules want validated, reads it out and waits
+/// for the user to answer. Without an answer the hook keeps its own decision,
+/// which leaves the confirmation to the terminal.
+pub fn request(app: &AppHandle, request: DecisionRequest) -> DecisionReply {
+    // The hook's own timeout started when it sent the request, so reading the
+    // call out must not add to the time the user has
+    let deadline = Instant::now() + APPROVAL_TIMEOUT;
+    let tool_use = ToolUse { tool_name: request.tool_name.clone(), tool_input: request.tool_input };
+    let (sender, receiver) = mpsc::channel();
+    let (approval, read_out) = {
+        let mut pending = PENDING.lock().unwrap();
+        let approval = ApprovalRequest {
+            id: pending.next_id,
+            session_id: request.session_id,
+            cwd: request.cwd,
+            tool_name: request.tool_name,
+            summary: request.summary,
+            detail: tool_use.detail(),
+            truncated: tool_use.summary_is_truncated(),
+            reason: request.reason,
+            timeout_secs: APPROVAL_TIMEOUT.as_secs(),
+        };
+        pending.next_id += 1;
+        pending.waiting.insert(approval.id, Waiting { sender, approval: approval.clone() });
+        let read_out = pending.presented.is_none();
+        if read_out {
+            pending.presented = Some(approval.id);
+        }
+        (approval, read_out)
+    };
+    let id = approval.id;
+
+    println!("Waiting for approval #{}: {}", id, approval.summary);
+    if let Err(e) = show(app, &approval) {
+        eprintln!("Failed to show approval #{}: {}", id, e);
+    }
+    if read_out {
+        if let Err(e) = present(app, &approval) {
+            eprintln!("Failed to read out approval #{}: {}", id, e);
+        }
+    }
+
+    let decision = receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())).ok();
+    let next = {
+        let mut pending = PENDING.lock().unwrap();
+        pending.waiting.remove(&id);
+        if pending.presented == Some(id) {
+            pending.presented = pending.waiting.keys().next().copied();
+            pending.presented.and_then(|next| pending.waiting.get(&next)).map(|next| next.approval.clone())
+        } else {
+            None
+        }
+    };
+    // An answer that came in just before it stopped being pending still counts
+    let decision = decision.or_else(|| receiver.try_recv().ok());
+    if let Err(e) = app.emit("approval-resolved", ApprovalResolved { id, decision }) {
+        eprintln!("Failed to emit approval-resolved event: {}", e);
+    }
+    if let Some(next) = next {
+        if let Err(e) = present(app, &next) {
+            eprintln!("Failed to read out approval #{}: {}", next.id, e);
+        }
+    }
+
+    match decision {
+        Some(decision) => {
+            println!("Approval #{} answered: {:?}", id, decision);
+            let reason = match decision {
+                PermissionDecision::Ask => "the user in the Vocal app; confirm in the terminal",
+                _ => "the user in the Vocal app",
+            };
+            DecisionReply {
+                decision: Some(decision),
+                reason: Some(reason.to_string()),
+            }
+        }
+        None => {
+            println!("Approval #{} timed out - leaving it to the terminal", id);
+            DecisionReply::default()
+        }
+    }
+}
+
+/// Answers a pending approval. A call whose summary was cut can't be
+/// approved here, only denied or left to the terminal.
+pub fn resolve(id: u64, decision: PermissionDecision) -> Result<(), String> {
+    let mut pending = PENDING.lock().unwrap();
+    let waiting = pending
+        .waiting
+        .get(&id)
+        .ok_or_else(|| format!("Approval #{} is no longer pending", id))?;
+    if decision == PermissionDecision::Allow && waiting.approval.truncated {
+        return Err(format!("Approval #{} is too long to approve here - deny it or confirm in the terminal", id));
+    }
+    let waiting = pending.waiting.remove(&id).expect("approval is pending");
+    waiting
+        .sender
+        .send(decision)
+        .map_err(|_| format!("Approval #{} is no longer pending", id))
+}
+
+/// Reads a spoken answer; anything that sounds like a refusal wins, so an
+/// unclear answer never approves.
+pub fn parse_spoken_answer(text: &str) -> Option<PermissionDecision> {
+    const DENY: &[&str] = &["deny", "no", "nope", "reject", "stop", "cancel", "don't", "dont", "not", "never"];
+    const APPROVE: &[&str] = &["approve", "approved", "yes", "yeah", "yep", "allow", "ok", "okay", "sure", "go", "accept"];
+    const ASK: &[&str] = &["terminal", "later", "skip"];
+
+    // Transcripts often use typographic apostrophes ("don’t")
+    let lowercase = text.to_lowercase().replace('’', "'");
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let said = |candidates: &[&str]| words.iter().any(|word| candidates.contains(word));
+
+    if said(DENY) {
+        Some(PermissionDecision::Deny)
+    } else if said(ASK) {
+        Some(PermissionDecision::Ask)
+    } else if said(APPROVE) {
+        Some(PermissionDecision::Allow)
+    } else {
+        None
+    }
+}
+
+/// Brings up the dictation window and adds the pending call to its queue.
+fn show(app: &AppHandle, approval: &ApprovalRequest) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Window not found")?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())?;
+    window.emit("approval-requested", approval).map_err(|e| e.to_string())
+}
+
+/// Reads the pending call out and starts listening for the answer.
+fn present(app: &AppHandle, approval: &ApprovalRequest) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("Window not found")?;
+
+    // Spoken before recording starts so the question isn't transcribed as the answer
+    let question = if approval.truncated {
+        format!("Claude Code wants to {} It is too long to approve by voice. Deny, or confirm in the terminal?", lowercase_first(&approval.summary))
+    } else {
+        format!("Claude Code wants to {}. Approve or deny?", lowercase_first(&approval.summary))
+    };
+    if let Err(e) = crate::current_config(app).speech.build().and_then(|speaker| speaker.speak(&question)) {
+        eprintln!("Failed to read out approval #{}: {}", approval.id, e);
+    }
+
+    window.emit("start-recording", ()).map_err(|e| e.to_string())
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spoken_answers() {
+        assert_eq!(parse_spoken_answer("Approve."), Some(PermissionDecision::Allow));
+        assert_eq!(parse_spoken_answer("Yes, go ahead"), Some(PermissionDecision::Allow));
+        assert_eq!(parse_spoken_answer("Deny"), Some(PermissionDecision::Deny));
+        assert_eq!(parse_spoken_answer("No."), Some(PermissionDecision::Deny));
+        assert_eq!(parse_spoken_answer("Don't approve that"), Some(PermissionDecision::Deny));
+        assert_eq!(parse_spoken_answer("Don’t approve that"), Some(PermissionDecision::Deny));
+        assert_eq!(parse_spoken_answer("I'll check in the terminal"), Some(PermissionDecision::Ask));
+        assert_eq!(parse_spoken_answer("Hmm, what's the weather like"), None);
+        assert_eq!(parse_spoken_answer(""), None);
+    }
+
+    #[test]
+    fn test_resolve_unknown_approval() {
+        assert_eq!(resolve(u64::MAX, PermissionDecision::Allow).unwrap_err(), format!("Approval #{} is no longer pending", u64::MAX));
+    }
+
+    #[test]
+    fn test_truncated_call_cannot_be_approved_here() {
+        let (sender, receiver) = mpsc::channel();
+        let approval = ApprovalRequest {
+            id: u64::MAX - 1,
+            session_id: "a".to_string(),
+            cwd: "/tmp".to_string(),
+            tool_name: "Bash".to_string(),
+            summary: "Run x…".to_string(),
+            detail: Some("x".repeat(500)),
+            truncated: true,
+            reason: "unknown command".to_string(),
+            timeout_secs: 25,
+        };
+        PENDING.lock().unwrap().waiting.insert(approval.id, Waiting { sender, approval: approval.clone() });
+
+        assert!(resolve(approval.id, PermissionDecision::Allow).unwrap_err().contains("confirm in the terminal"));
+        assert!(receiver.try_recv().is_err());
+        resolve(approval.id, PermissionDecision::Deny).unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), PermissionDecision::Deny);
+    }
+
+    #[test]
+    fn test_lowercase_first() {
+        assert_eq!(lowercase_first("Write /etc/hosts"), "write /etc/hosts");
+        assert_eq!(lowercase_first(""), "");
+    }
+}
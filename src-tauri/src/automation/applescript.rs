@@ -27,23 +27,48 @@ impl AppleScriptExecutor {
              .replace("\t", "\\t")
     }
     
+    pub fn create_notification_script(title: &str, message: &str) -> String {
+        format!(
+            "display notification \"{}\" with title \"{}\"",
+            Self::escape_string(message),
+            Self::escape_string(title)
+        )
+    }
+    
     pub fn create_terminal_injection_script(
         prompt_text: &str,
         terminal_app: &str,
-        session_info: Option<&crate::hooks::state::SessionInfo>,
+        session_info: Option<&crate::hooks::sessions::SessionInfo>,
+        exact: bool,
     ) -> String {
         let escaped_prompt = Self::escape_string(prompt_text);
         
         match terminal_app {
-            "Terminal" => Self::create_terminal_app_script(&escaped_prompt, session_info),
-            "iTerm2" => Self::create_iterm2_script(&escaped_prompt, session_info),
+            "Terminal" => Self::create_terminal_app_script(&escaped_prompt, session_info, exact),
+            "iTerm2" => Self::create_iterm2_script(&escaped_prompt, session_info, exact),
             _ => Self::create_generic_terminal_script(&escaped_prompt),
         }
     }
     
-    fn create_terminal_app_script(prompt_text: &str, session_info: Option<&crate::hooks::state::SessionInfo>) -> String {
+    /// With `exact`, a session tab that is gone is an error instead of
+    /// falling back to the frontmost one.
+    fn create_terminal_app_script(prompt_text: &str, session_info: Option<&crate::hooks::sessions::SessionInfo>, exact: bool) -> String {
         if let Some(session) = session_info {
             if !session.term_session.is_empty() {
+                let fallback = if exact {
+                    r#"error "The session's Terminal tab was not found""#
+                } else {
+                    r#"-- Fallback to frontmost terminal
+                    tell application "Terminal"
+                        activate
+                        tell application "System Events"
+                            set the clipboard to promptText
+                            key code 9 using {command down}
+                            delay 0.1
+                            key code 36
+                        end tell
+                    end tell"#
+                };
                 return format!(r#"
                     set promptText to "{}"
                     set termSession to "{}"
@@ -67,17 +92,8 @@ impl AppleScriptExecutor {
                         end repeat
                     end tell
                     
-                    -- Fallback to frontmost terminal
-                    tell application "Terminal"
-                        activate
-                        tell application "System Events"
-                            set the clipboard to promptText
-                            key code 9 using {{command down}}
-                            delay 0.1
-                            key code 36
-                        end tell
-                    end tell
-                "#, prompt_text, session.term_session);
+                    {}
+                "#, prompt_text, session.term_session, fallback);
             }
         }
         
@@ -97,9 +113,20 @@ impl AppleScriptExecutor {
         "#, prompt_text)
     }
     
-    fn create_iterm2_script(prompt_text: &str, session_info: Option<&crate::hooks::state::SessionInfo>) -> String {
+    fn create_iterm2_script(prompt_text: &str, session_info: Option<&crate::hooks::sessions::SessionInfo>, exact: bool) -> String {
         if let Some(session) = session_info {
             if !session.iterm_session.is_empty() {
+                let fallback = if exact {
+                    r#"error "The session's iTerm2 tab was not found""#
+                } else {
+                    r#"-- Fallback to current session
+                    tell application "iTerm2"
+                        activate
+                        tell current session of current tab of current window
+                            write text promptText
+                        end tell
+                    end tell"#
+                };
                 return format!(r#"
                     set promptText to "{}"
                     set itermSession to "{}"
@@ -120,14 +147,8 @@ impl AppleScriptExecutor {
                         end repeat
                     end tell
                     
-                    -- Fallback to current session
-                    tell application "iTerm2"
-                        activate
-                        tell current session of current tab of current window
-                            write text promptText
-                        end tell
-                    end tell
-                "#, prompt_text, session.iterm_session);
+                    {}
+                "#, prompt_text, session.iterm_session, fallback);
             }
         }
         
@@ -1,5 +1,7 @@
 use std::error::Error;
-use crate::hooks::state::{SessionInfo, load_session_info};
+use std::process::Command;
+use crate::hooks::sessions::SessionInfo;
+use crate::hooks::state::load_session_info;
 use crate::automation::applescript::AppleScriptExecutor;
 
 pub struct TerminalInjector;
@@ -15,14 +17,42 @@ impl TerminalInjector {
             }
         };
         
-        // Detect the terminal application
-        let terminal_app = Self::detect_terminal_application()?;
+        Self::inject_text_to_session(text, session_info.as_ref())
+    }
+    
+    /// Types `text` into the terminal of `session_info`, or into the frontmost
+    /// terminal without one or when its terminal can't be found.
+    pub fn inject_text_to_session(text: &str, session_info: Option<&SessionInfo>) -> Result<(), Box<dyn Error>> {
+        Self::inject(text, session_info, false)
+    }
+    
+    /// Types `text` into the terminal of `session` and nowhere else; fails
+    /// when that terminal can't be addressed.
+    pub fn inject_text_to_exact_session(text: &str, session: &SessionInfo) -> Result<(), Box<dyn Error>> {
+        Self::inject(text, Some(session), true)
+    }
+    
+    fn inject(text: &str, session_info: Option<&SessionInfo>, exact: bool) -> Result<(), Box<dyn Error>> {
+        // tmux addresses the pane directly, whichever terminal shows it
+        if let Some((socket, pane)) = session_info.and_then(SessionInfo::tmux_target) {
+            return Self::inject_tmux(text, socket, pane);
+        }
+        
+        // A known session's terminal wins over whatever is frontmost
+        let terminal_app = match (session_info, session_info.and_then(Self::terminal_application_for)) {
+            (_, Some(app)) => app.to_string(),
+            (Some(session), None) if exact => {
+                return Err(format!("Session {} doesn't run in Terminal, iTerm2 or tmux, so Vocal can't address it", session.session_id).into());
+            }
+            _ => Self::detect_terminal_application()?,
+        };
         
         // Create appropriate AppleScript
         let script = AppleScriptExecutor::create_terminal_injection_script(
             text,
             &terminal_app,
-            session_info.as_ref(),
+            session_info,
+            exact,
         );
         
         // Execute the script
@@ -31,6 +61,7 @@ impl TerminalInjector {
                 println!("Successfully injected text into {} session", terminal_app);
                 Ok(())
             }
+            Err(e) if exact => Err(format!("Could not reach the {} tab of the session: {}", terminal_app, e).into()),
             Err(e) => {
                 eprintln!("Failed to inject text: {}", e);
                 
@@ -41,6 +72,31 @@ impl TerminalInjector {
         }
     }
     
+    /// Types `text` into a tmux pane and presses Enter.
+    fn inject_tmux(text: &str, socket: &str, pane: &str) -> Result<(), Box<dyn Error>> {
+        for keys in [&["-l", "--", text][..], &["Enter"]] {
+            let output = Command::new("tmux")
+                .args(["-S", socket, "send-keys", "-t", pane])
+                .args(keys)
+                .output()?;
+            if !output.status.success() {
+                return Err(format!("tmux send-keys failed: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+            }
+        }
+        println!("Successfully injected text into tmux pane {}", pane);
+        Ok(())
+    }
+    
+    fn terminal_application_for(session: &SessionInfo) -> Option<&'static str> {
+        if !session.iterm_session.is_empty() {
+            Some("iTerm2")
+        } else if !session.term_session.is_empty() {
+            Some("Terminal")
+        } else {
+            None
+        }
+    }
+    
     fn detect_terminal_application() -> Result<String, Box<dyn Error>> {
         let script = r#"
             tell application "System Events"
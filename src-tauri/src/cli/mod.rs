@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "vocal")]
@@ -20,6 +21,77 @@ pub enum Commands {
         #[command(subcommand)]
         hook_type: HookCommands,
     },
+
+    /// List, search or delete past dictations
+    History {
+        /// Only show entries whose transcript, prompt or session contains this text
+        #[arg(short, long)]
+        search: Option<String>,
+
+        /// Maximum number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Print entries as JSON lines
+        #[arg(long)]
+        json: bool,
+
+        /// Delete the entry with this id instead of listing
+        #[arg(long, value_name = "ID")]
+        delete: Option<String>,
+    },
+
+    /// Inspect the hands-free safety policy
+    Policy {
+        #[command(subcommand)]
+        command: PolicyCommands,
+    },
+
+    /// Send a recent refined prompt to the Claude Code session again
+    Resend {
+        /// Which prompt to send, counting back from the most recent (1)
+        #[arg(default_value_t = 1)]
+        n: usize,
+
+        /// Edit the prompt in $VISUAL/$EDITOR before sending
+        #[arg(short, long)]
+        edit: bool,
+
+        /// Print the prompt instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Inspect Claude Code session transcripts
+    Transcript {
+        #[command(subcommand)]
+        command: TranscriptCommands,
+    },
+
+    /// Roll back the most recent hands-free edits
+    Undo {
+        /// Number of edits to roll back, most recent first
+        #[arg(default_value_t = 1)]
+        n: usize,
+
+        /// Only edits made in this session (an id or the start of one)
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Overwrite files that changed again after the edit
+        #[arg(long)]
+        force: bool,
+
+        /// List the edits instead of rolling them back
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show what hands-free mode approved, blocked and ran
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -38,4 +110,91 @@ pub enum HookCommands {
     /// Handle UserPromptSubmit hook events
     #[command(name = "user-prompt-submit")]
     UserPromptSubmit,
-}
\ No newline at end of file
+    
+    /// Handle Notification hook events by showing the message
+    Notification,
+    
+    /// Handle SubagentStop hook events
+    #[command(name = "subagent-stop")]
+    SubagentStop,
+    
+    /// Handle PreCompact hook events
+    #[command(name = "pre-compact")]
+    PreCompact,
+    
+    /// Handle SessionStart hook events to register the session
+    #[command(name = "session-start")]
+    SessionStart,
+    
+    /// Handle SessionEnd hook events to clean up the session registry
+    #[command(name = "session-end")]
+    SessionEnd,
+}
+
+#[derive(Subcommand)]
+pub enum PolicyCommands {
+    /// Show the decision the safety policy makes for a tool call
+    Check {
+        /// Tool name, e.g. Bash, Edit or Read
+        #[arg(long)]
+        tool: String,
+        
+        /// Tool input as JSON, e.g. '{"command": "cargo test"}'
+        #[arg(long, default_value = "{}")]
+        input: String,
+        
+        /// Working directory of the Claude Code session (defaults to the current directory)
+        #[arg(long)]
+        cwd: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TranscriptCommands {
+    /// Summarize the last turn of a transcript: tools used, files changed and tokens
+    Summarize {
+        /// Transcript JSONL file, e.g. ~/.claude/projects/<project>/<session>.jsonl
+        path: PathBuf,
+
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Show the most recent audit entries
+    Tail {
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Print entries as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Search the audit log
+    Query {
+        /// Session id, or the start of one
+        #[arg(long)]
+        session: Option<String>,
+
+        /// Tool name, e.g. Bash or Edit
+        #[arg(long)]
+        tool: Option<String>,
+
+        /// Only entries newer than this: 30m, 2h, 7d, 2026-10-17 or an RFC 3339 timestamp
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Maximum number of entries to show, the most recent ones
+        #[arg(short = 'n', long, default_value_t = 100)]
+        limit: usize,
+
+        /// Print entries as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+}
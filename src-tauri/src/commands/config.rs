@@ -0,0 +1,59 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+use crate::config::Config;
+
+#[tauri::command]
+pub async fn get_config(config: State<'_, Mutex<Config>>) -> Result<Config, String> {
+    let config = config.lock().map_err(|e| e.to_string())?;
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn set_config(
+    app: AppHandle,
+    config: Config,
+    state: State<'_, Mutex<Config>>,
+) -> Result<(), String> {
+    println!("Saving configuration to {}", crate::config::config_path().display());
+    
+    let previous_hotkey = state.lock().map_err(|e| e.to_string())?.hotkey.clone();
+    let hotkey_changed = config.hotkey != previous_hotkey;
+    let restore_hotkey = || {
+        if let Err(e) = crate::register_dictation_shortcut(&app, &previous_hotkey) {
+            eprintln!("Failed to restore shortcut '{}': {}", previous_hotkey, e);
+        }
+    };
+    
+    // Re-register the shortcut only when it actually changed, and before
+    // saving so an invalid hotkey is never persisted
+    if hotkey_changed {
+        if let Err(e) = crate::register_dictation_shortcut(&app, &config.hotkey) {
+            restore_hotkey();
+            return Err(e);
+        }
+    }
+    
+    if let Err(e) = config.save() {
+        if hotkey_changed {
+            restore_hotkey();
+        }
+        return Err(format!("Failed to save configuration: {}", e));
+    }
+    if hotkey_changed {
+        println!("Global shortcut updated to: {}", config.hotkey);
+    }
+    
+    *state.lock().map_err(|e| e.to_string())? = config.clone();
+    
+    // Keep the other windows' copies in sync
+    app.emit("config-changed", config)
+        .map_err(|e| format!("Failed to emit config-changed event: {}", e))?;
+    
+    println!("Configuration saved");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_default_config() -> Result<Config, String> {
+    Ok(Config::default())
+}
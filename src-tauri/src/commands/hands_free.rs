@@ -1,9 +1,27 @@
-use tauri::{AppHandle, Emitter};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
 use crate::automation::terminal::TerminalInjector;
+use crate::hooks::sessions::{split_spoken_target, SessionInfo, SessionRegistry};
+use crate::hooks::transcript::{Transcript, TurnSummary};
+use crate::approval;
 use crate::hooks::state;
+use crate::hooks::PermissionDecision;
 
+// Session picked in the UI; `None` routes every prompt automatically
+static SELECTED_SESSION: Mutex<Option<String>> = Mutex::new(None);
+
+/// Sends a prompt to a Claude Code session and returns the session it went to.
+/// `target` (a session id, directory or project name) wins; otherwise a spoken
+/// prefix like "in the backend project, ..." in the prompt or its raw
+/// `transcript`, then the session selected in the UI, then the session that
+/// most recently stopped.
 #[tauri::command]
-pub async fn inject_prompt_to_claude_session(prompt: String) -> Result<(), String> {
+pub async fn inject_prompt_to_claude_session(
+    prompt: String,
+    target: Option<String>,
+    transcript: Option<String>,
+) -> Result<Option<SessionInfo>, String> {
     println!("Injecting prompt to Claude Code session: {} chars", prompt.len());
     
     // Check if hands-free mode is active
@@ -11,14 +29,119 @@ pub async fn inject_prompt_to_claude_session(prompt: String) -> Result<(), Strin
         return Err("Hands-free mode is not active".to_string());
     }
     
-    // Inject the prompt into the terminal
-    TerminalInjector::inject_text_to_claude_session(&prompt)
-        .map_err(|e| format!("Failed to inject prompt: {}", e))?;
+    let (route, prompt) = resolve_target_session(&prompt, target.as_deref(), transcript.as_deref())?;
+    
+    // Inject the prompt into the terminal; a session the user chose must not
+    // end up in whatever terminal happens to be frontmost
+    let session = match route {
+        Route::Chosen(session) => {
+            println!("Targeting chosen session {} in {}", session.session_id, session.cwd);
+            TerminalInjector::inject_text_to_exact_session(&prompt, &session)
+                .map_err(|e| format!("Failed to inject prompt into the {} session: {}", session.project_name(), e))?;
+            Some(session)
+        }
+        Route::Automatic(session) => {
+            match &session {
+                Some(session) => println!("Targeting session {} in {}", session.session_id, session.cwd),
+                None => println!("No registered session, targeting the frontmost terminal"),
+            }
+            TerminalInjector::inject_text_to_session(&prompt, session.as_ref())
+                .map_err(|e| format!("Failed to inject prompt: {}", e))?;
+            session
+        }
+    };
     
     println!("Successfully injected prompt to Claude Code session");
+    Ok(session)
+}
+
+/// Where a prompt goes: a session named by the caller, a spoken prefix or
+/// the UI selection, or the automatic pick.
+enum Route {
+    Chosen(SessionInfo),
+    Automatic(Option<SessionInfo>),
+}
+
+fn resolve_target_session(
+    prompt: &str,
+    target: Option<&str>,
+    transcript: Option<&str>,
+) -> Result<(Route, String), String> {
+    let registry = SessionRegistry::open();
+    let sessions = registry
+        .list()
+        .map_err(|e| format!("Failed to load sessions: {}", e))?;
+    
+    if let Some(target) = target {
+        let session = sessions
+            .into_iter()
+            .find(|session| session.matches(target))
+            .ok_or_else(|| format!("No Claude Code session matches '{}'", target))?;
+        return Ok((Route::Chosen(session), prompt.to_string()));
+    }
+    
+    // The refinement may reword the prefix, so the raw transcript is checked
+    // too; only a prefix found in the prompt itself is stripped from it
+    if let Some((session, rest)) = split_spoken_target(prompt, &sessions) {
+        return Ok((Route::Chosen(session), rest.to_string()));
+    }
+    if let Some((session, _)) = transcript.and_then(|text| split_spoken_target(text, &sessions)) {
+        return Ok((Route::Chosen(session), prompt.to_string()));
+    }
+    
+    let selected = SELECTED_SESSION.lock().unwrap().clone();
+    if let Some(selected) = selected {
+        let session = sessions
+            .into_iter()
+            .find(|session| session.session_id == selected)
+            .ok_or_else(|| format!("The selected session {} has ended - pick another one or automatic routing", selected))?;
+        return Ok((Route::Chosen(session), prompt.to_string()));
+    }
+    
+    let session = registry
+        .last_stopped()
+        .map_err(|e| format!("Failed to load sessions: {}", e))?;
+    Ok((Route::Automatic(session), prompt.to_string()))
+}
+
+/// Pins prompts to one session; `None` goes back to automatic routing.
+#[tauri::command]
+pub async fn set_target_session(session_id: Option<String>) -> Result<(), String> {
+    println!("Target session: {}", session_id.as_deref().unwrap_or("automatic"));
+    *SELECTED_SESSION.lock().unwrap() = session_id;
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_target_session() -> Result<Option<String>, String> {
+    Ok(SELECTED_SESSION.lock().unwrap().clone())
+}
+
+/// Claude Code sessions seen by the hooks, most recently active first.
+#[tauri::command]
+pub async fn list_sessions() -> Result<Vec<SessionInfo>, String> {
+    SessionRegistry::open()
+        .list()
+        .map_err(|e| format!("Failed to list sessions: {}", e))
+}
+
+/// What Claude did in the last turn of a session: tools used, files changed
+/// and tokens. `None` when the hooks haven't recorded the session's transcript.
+#[tauri::command]
+pub async fn get_session_summary(session_id: String) -> Result<Option<TurnSummary>, String> {
+    let session = SessionRegistry::open()
+        .get(&session_id)
+        .map_err(|e| format!("Failed to read sessions: {}", e))?
+        .ok_or_else(|| format!("Unknown session {}", session_id))?;
+    if session.transcript_path.is_empty() {
+        return Ok(None);
+    }
+
+    let transcript = Transcript::read(Path::new(&session.transcript_path))
+        .map_err(|e| format!("Failed to read transcript {}: {}", session.transcript_path, e))?;
+    Ok(Some(transcript.last_turn().summary()))
+}
+
 #[tauri::command]
 pub async fn activate_hands_free_mode() -> Result<(), String> {
     println!("Activating hands-free mode...");
@@ -46,6 +169,12 @@ pub async fn get_hands_free_status() -> Result<bool, String> {
     Ok(state::is_hands_free_active())
 }
 
+/// Why hands-free mode last switched itself off, e.g. a cycle limit.
+#[tauri::command]
+pub async fn get_hands_free_stop_reason() -> Result<Option<String>, String> {
+    Ok(state::hands_free_stop_reason())
+}
+
 #[tauri::command]
 pub async fn trigger_emergency_stop() -> Result<(), String> {
     println!("Triggering emergency stop for hands-free mode...");
@@ -57,6 +186,23 @@ pub async fn trigger_emergency_stop() -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn clear_emergency_stop() -> Result<(), String> {
+    state::clear_emergency_stop()
+        .map_err(|e| format!("Failed to clear emergency stop: {}", e))?;
+    
+    println!("Emergency stop cleared");
+    Ok(())
+}
+
+/// Picks up a cycle trigger left from before the app started; the frontend
+/// calls it once it listens for `restart-recording`.
+#[tauri::command]
+pub async fn check_cycle_trigger(app: AppHandle) -> Result<(), String> {
+    crate::watcher::handle_cycle_trigger(&app);
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_terminal_info() -> Result<String, String> {
     match TerminalInjector::get_active_terminal_info() {
@@ -70,23 +216,21 @@ pub async fn is_terminal_active() -> Result<bool, String> {
     Ok(TerminalInjector::is_terminal_application_active())
 }
 
+/// Answers a pending tool-call approval, either directly (buttons, keys) or
+/// with the transcript of a spoken answer; returns the decision taken.
 #[tauri::command]
-pub async fn check_cycle_trigger(app: AppHandle) -> Result<bool, String> {
-    let trigger_path = state::cycle_trigger_path();
-    
-    if trigger_path.exists() {
-        println!("Cycle trigger detected - restarting recording");
-        
-        // Clear the trigger file
-        state::clear_cycle_trigger()
-            .map_err(|e| format!("Failed to clear cycle trigger: {}", e))?;
-        
-        // Emit event to restart recording
-        app.emit("restart-recording", ())
-            .map_err(|e| format!("Failed to emit restart-recording event: {}", e))?;
-        
-        Ok(true)
-    } else {
-        Ok(false)
-    }
-}
\ No newline at end of file
+pub async fn answer_approval(
+    id: u64,
+    decision: Option<PermissionDecision>,
+    transcript: Option<String>,
+) -> Result<PermissionDecision, String> {
+    let decision = match (decision, transcript) {
+        (Some(decision), _) => decision,
+        (None, Some(transcript)) => approval::parse_spoken_answer(&transcript)
+            .ok_or_else(|| format!("Didn't catch an answer in \"{}\" - say approve or deny", transcript.trim()))?,
+        (None, None) => return Err("No answer given".to_string()),
+    };
+    approval::resolve(id, decision)?;
+    println!("Approval #{} answered with {:?}", id, decision);
+    Ok(decision)
+}
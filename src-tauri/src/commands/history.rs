@@ -0,0 +1,139 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use crate::automation::terminal::TerminalInjector;
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::hooks::state;
+
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+// The entry created by the last transcription, so the refinement that follows
+// lands on the same record: (entry id, raw transcript)
+static LAST_TRANSCRIPTION: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+fn target_session() -> Option<String> {
+    if !state::is_hands_free_active() {
+        return None;
+    }
+    state::load_session_info()
+        .ok()
+        .map(|info| info.session_id)
+        .filter(|session_id| !session_id.is_empty())
+}
+
+/// Records a finished transcription. History is best effort: failures are
+/// logged and never fail the dictation.
+pub(crate) fn record_transcription(text: &str, provider: &str, elapsed: Duration) {
+    let entry = HistoryEntry {
+        transcription_provider: Some(provider.to_string()),
+        transcription_ms: Some(elapsed.as_millis() as u64),
+        ..HistoryEntry::new(text)
+    };
+
+    if let Err(e) = HistoryStore::open().append(&entry) {
+        println!("Failed to record transcription in history: {}", e);
+        return;
+    }
+
+    if let Ok(mut last) = LAST_TRANSCRIPTION.lock() {
+        *last = Some((entry.id, text.to_string()));
+    }
+}
+
+/// Attaches a refinement to the transcription it came from, or records it on
+/// its own when the text did not come from the last transcription.
+pub(crate) fn record_refinement(text: &str, refined: &str, provider: &str, elapsed: Duration) {
+    let store = HistoryStore::open();
+    let target_session = target_session();
+    let elapsed_ms = elapsed.as_millis() as u64;
+
+    let last = LAST_TRANSCRIPTION.lock().ok().and_then(|mut last| last.take());
+    let result = match last {
+        Some((id, raw)) if raw == text => store
+            .update(&id, |entry| {
+                entry.refined_prompt = Some(refined.to_string());
+                entry.refinement_provider = Some(provider.to_string());
+                entry.refinement_ms = Some(elapsed_ms);
+                entry.target_session = target_session.clone();
+            })
+            .map(|_| ()),
+        _ => store.append(&HistoryEntry {
+            refined_prompt: Some(refined.to_string()),
+            refinement_provider: Some(provider.to_string()),
+            refinement_ms: Some(elapsed_ms),
+            target_session,
+            ..HistoryEntry::new(text)
+        }),
+    };
+
+    if let Err(e) = result {
+        println!("Failed to record refinement in history: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn list_history(limit: Option<usize>, offset: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+    HistoryStore::open()
+        .list(limit.unwrap_or(DEFAULT_HISTORY_LIMIT), offset.unwrap_or(0))
+        .map_err(|e| format!("Failed to read history: {}", e))
+}
+
+#[tauri::command]
+pub async fn search_history(query: String, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+    HistoryStore::open()
+        .search(&query, limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+        .map_err(|e| format!("Failed to search history: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_history_entry(app: AppHandle, id: String) -> Result<bool, String> {
+    println!("Deleting history entry {}", id);
+    let deleted = HistoryStore::open()
+        .delete(&id)
+        .map_err(|e| format!("Failed to delete history entry: {}", e))?;
+
+    // The entry may be listed in the tray's recent prompts
+    if deleted {
+        crate::refresh_tray_menu(&app);
+    }
+    Ok(deleted)
+}
+
+/// Sends the `n`th most recent refined prompt (1 = latest) to the Claude Code
+/// session again, or `edited_prompt` in its place when the user changed it.
+/// Returns the text that was sent.
+#[tauri::command]
+pub async fn resend_recent_prompt(n: usize, edited_prompt: Option<String>) -> Result<String, String> {
+    let prompt = match edited_prompt.filter(|prompt| !prompt.trim().is_empty()) {
+        Some(prompt) => prompt,
+        None => HistoryStore::open()
+            .recent_prompt(n)
+            .map_err(|e| format!("Failed to read history: {}", e))?
+            .ok_or_else(|| format!("No refined prompt #{} in history", n))?,
+    };
+
+    println!("Re-sending recent prompt #{}: {} chars", n, prompt.len());
+    send_again(prompt)
+}
+
+/// Sends the refined prompt of history entry `id` again; the tray menu keys
+/// its items by entry so a stale menu can't send a different prompt.
+pub(crate) fn resend_history_entry(id: &str) -> Result<String, String> {
+    let prompt = HistoryStore::open()
+        .get(id)
+        .map_err(|e| format!("Failed to read history: {}", e))?
+        .and_then(|entry| entry.refined_prompt)
+        .filter(|prompt| !prompt.trim().is_empty())
+        .ok_or_else(|| format!("History entry {} is gone or has no refined prompt", id))?;
+
+    println!("Re-sending prompt of history entry {}: {} chars", id, prompt.len());
+    send_again(prompt)
+}
+
+fn send_again(prompt: String) -> Result<String, String> {
+    TerminalInjector::inject_text_to_claude_session(&prompt)
+        .map_err(|e| format!("Failed to inject prompt: {}", e))?;
+
+    println!("Successfully re-sent prompt to Claude Code session");
+    Ok(prompt)
+}
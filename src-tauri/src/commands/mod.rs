@@ -1,5 +1,11 @@
 pub mod hands_free;
 pub mod cli_setup;
+pub mod config;
+pub mod secrets;
+pub mod history;
 
 pub use hands_free::*;
-pub use cli_setup::*;
\ No newline at end of file
+pub use cli_setup::*;
+pub use config::*;
+pub use secrets::*;
+pub use history::*;
\ No newline at end of file
@@ -0,0 +1,49 @@
+use tauri::{AppHandle, Emitter};
+use crate::secrets::{SecretName, SecretStatus, SecretStore, Secrets};
+
+// Keyring backends block on D-Bus/Keychain calls, so keep them off the async runtime
+async fn with_secrets<T, F>(operation: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Secrets) -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || operation(&Secrets::open()))
+        .await
+        .map_err(|e| format!("Secrets task failed: {}", e))?
+}
+
+/// Looks up an API key for a backend command. Missing keys come back empty.
+pub async fn lookup_api_key(name: SecretName) -> Result<String, String> {
+    with_secrets(move |secrets| secrets.api_key(name)).await
+}
+
+#[tauri::command]
+pub async fn get_secret_status() -> Result<SecretStatus, String> {
+    with_secrets(|secrets| secrets.status()).await
+}
+
+// Lets every window refresh its "key is set" indicators
+async fn emit_status(app: &AppHandle) -> Result<(), String> {
+    let status = get_secret_status().await?;
+    app.emit("secrets-changed", status)
+        .map_err(|e| format!("Failed to emit secrets-changed event: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_secret(app: AppHandle, name: SecretName, value: String) -> Result<(), String> {
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Err("Secret value must not be empty".to_string());
+    }
+
+    println!("Storing {}", name.as_str());
+    with_secrets(move |secrets| secrets.set(name, &value)).await?;
+    emit_status(&app).await
+}
+
+#[tauri::command]
+pub async fn delete_secret(app: AppHandle, name: SecretName) -> Result<(), String> {
+    println!("Deleting {}", name.as_str());
+    with_secrets(move |secrets| secrets.delete(name)).await?;
+    emit_status(&app).await
+}
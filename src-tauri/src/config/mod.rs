@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::hooks::git::GitConfig;
+use crate::hooks::HandsFreeConfig;
+use crate::refinement::RefinementConfig;
+use crate::speech::SpeechConfig;
+use crate::transcription::TranscriptionConfig;
+
+pub const DEFAULT_HOTKEY: &str = "CommandOrControl+Shift+V";
+pub const DEFAULT_SYSTEM_PROMPT: &str = include_str!("default_system_prompt.txt");
+
+/// User settings persisted at `~/.vocal/config.toml`. API keys live in the
+/// secrets store, not here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    pub system_prompt: String,
+    pub hotkey: String,
+    pub transcription: TranscriptionConfig,
+    pub refinement: RefinementConfig,
+    /// Runaway-loop limits, also read by the hook binary
+    pub hands_free: HandsFreeConfig,
+    pub speech: SpeechConfig,
+    /// Checkpoints and branch protection, also read by the hook binary
+    pub git: GitConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            hotkey: DEFAULT_HOTKEY.to_string(),
+            transcription: TranscriptionConfig::default(),
+            refinement: RefinementConfig::default(),
+            hands_free: HandsFreeConfig::default(),
+            speech: SpeechConfig::default(),
+            git: GitConfig::default(),
+        }
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal")
+}
+
+pub fn config_path() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+impl Config {
+    /// Loads the saved configuration, falling back to defaults when none exists yet.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        Self::load_from(&config_path())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        self.save_to(&config_path())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|e| format!("Invalid configuration in {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    /// Writes to a temporary file and renames it over the old one so a crash
+    /// mid-write never leaves a truncated config behind.
+    pub fn save_to(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = toml::to_string_pretty(self)?;
+        let temp_path = path.with_extension("toml.tmp");
+        fs::write(&temp_path, content)?;
+
+        // Older versions kept API keys here; keep the file private regardless
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::TranscriptionProvider;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_file_loads_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::load_from(&temp_dir.path().join("config.toml")).unwrap();
+
+        assert_eq!(config, Config::default());
+        assert_eq!(config.hotkey, DEFAULT_HOTKEY);
+        assert!(!config.system_prompt.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".vocal/config.toml");
+
+        let mut config = Config {
+            hotkey: "Alt+Space".to_string(),
+            ..Default::default()
+        };
+        config.transcription.provider = TranscriptionProvider::OpenAiCompatible;
+        config.transcription.base_url = Some("http://whisper.internal/v1".to_string());
+        config.hands_free.max_cycles = 3;
+        config.speech.voice = Some("Samantha".to_string());
+        config.git.enabled = true;
+        config.save_to(&path).unwrap();
+
+        assert_eq!(Config::load_from(&path).unwrap(), config);
+        assert_eq!(HandsFreeConfig::load_from(&path).unwrap(), config.hands_free);
+        assert_eq!(GitConfig::load_from(&path).unwrap(), config.git);
+        assert!(!path.with_extension("toml.tmp").exists());
+    }
+
+    #[test]
+    fn test_partial_file_fills_in_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "hotkey = \"Alt+V\"\n\n[refinement]\nprovider = \"ollama\"\n").unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.hotkey, "Alt+V");
+        assert_eq!(config.system_prompt, DEFAULT_SYSTEM_PROMPT);
+        assert_eq!(config.refinement.provider, crate::refinement::RefinementProvider::Ollama);
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "hotkey = [").unwrap();
+
+        assert!(Config::load_from(&path).is_err());
+    }
+}
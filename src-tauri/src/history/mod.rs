@@ -0,0 +1,409 @@
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// One dictation: what was said, what it was refined into and where it went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub raw_transcript: String,
+    pub refined_prompt: Option<String>,
+    /// Claude Code session the prompt was meant for, when hands-free mode was on.
+    pub target_session: Option<String>,
+    pub transcription_provider: Option<String>,
+    pub transcription_ms: Option<u64>,
+    pub refinement_provider: Option<String>,
+    pub refinement_ms: Option<u64>,
+}
+
+impl HistoryEntry {
+    pub fn new(raw_transcript: &str) -> Self {
+        let created_at = Utc::now();
+        HistoryEntry {
+            id: entry_id(created_at),
+            created_at,
+            raw_transcript: raw_transcript.to_string(),
+            refined_prompt: None,
+            target_session: None,
+            transcription_provider: None,
+            transcription_ms: None,
+            refinement_provider: None,
+            refinement_ms: None,
+        }
+    }
+
+    /// Multi-line description for `vocal history`.
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!(
+            "{}  {}",
+            self.id,
+            self.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S")
+        )];
+
+        if let Some(session) = &self.target_session {
+            lines.push(format!("  session: {}", session));
+        }
+        lines.push(format!("  said:    {}", self.raw_transcript));
+        if let Some(prompt) = &self.refined_prompt {
+            lines.push(format!("  prompt:  {}", prompt.replace('\n', "\n           ")));
+        }
+
+        let timings: Vec<String> = [
+            (&self.transcription_provider, self.transcription_ms),
+            (&self.refinement_provider, self.refinement_ms),
+        ]
+        .into_iter()
+        .filter_map(|(provider, ms)| Some(format!("{} {}ms", provider.as_deref()?, ms?)))
+        .collect();
+        if !timings.is_empty() {
+            lines.push(format!("  timing:  {}", timings.join(", ")));
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        let contains = |text: &str| text.to_lowercase().contains(query);
+        contains(&self.raw_transcript)
+            || self.refined_prompt.as_deref().is_some_and(contains)
+            || self.target_session.as_deref().is_some_and(contains)
+    }
+}
+
+/// First line of `text`, shortened to `max_chars` for menus and listings.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    if line.chars().count() <= max_chars && !text.trim().contains('\n') {
+        return line.to_string();
+    }
+    let shortened: String = line.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", shortened.trim_end())
+}
+
+// Timestamps are unique enough for a single user's dictations and sort naturally
+fn entry_id(created_at: DateTime<Utc>) -> String {
+    let nanos = created_at
+        .timestamp_nanos_opt()
+        .unwrap_or_else(|| created_at.timestamp_micros() * 1000);
+    format!("{:x}", nanos)
+}
+
+pub fn history_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal")
+        .join("history.jsonl")
+}
+
+/// Append-only JSONL log of dictations, oldest first. Updates and deletes
+/// rewrite the file through a temporary file. The app and the CLI both write
+/// it, so every write holds an exclusive lock on `history.jsonl.lock`.
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        HistoryStore { path }
+    }
+
+    pub fn open() -> Self {
+        Self::new(history_path())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), Box<dyn Error>> {
+        self.locked(|| {
+            let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+            }
+
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+            Ok(())
+        })
+    }
+
+    /// All entries, oldest first. Lines that fail to parse (e.g. a write cut
+    /// short by a crash) are skipped rather than poisoning the whole history.
+    pub fn load(&self) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let file = fs::File::open(&self.path)?;
+        let mut entries = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("Skipping unreadable history line in {}: {}", self.path.display(), e),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Newest first, paged.
+    pub fn list(&self, limit: usize, offset: usize) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+        Ok(self.load()?.into_iter().rev().skip(offset).take(limit).collect())
+    }
+
+    /// Case-insensitive substring search over transcripts, prompts and sessions, newest first.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+        let query = query.trim().to_lowercase();
+        Ok(self
+            .load()?
+            .into_iter()
+            .rev()
+            .filter(|entry| entry.matches(&query))
+            .take(limit)
+            .collect())
+    }
+
+    /// Entries with a refined prompt, newest first.
+    pub fn recent_prompts(&self, limit: usize) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .rev()
+            .filter(|entry| entry.refined_prompt.as_deref().is_some_and(|prompt| !prompt.trim().is_empty()))
+            .take(limit)
+            .collect())
+    }
+
+    /// The `n`th most recent refined prompt, counting from 1.
+    pub fn recent_prompt(&self, n: usize) -> Result<Option<String>, Box<dyn Error>> {
+        if n == 0 {
+            return Ok(None);
+        }
+        Ok(self
+            .recent_prompts(n)?
+            .into_iter()
+            .nth(n - 1)
+            .and_then(|entry| entry.refined_prompt))
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<HistoryEntry>, Box<dyn Error>> {
+        Ok(self.load()?.into_iter().find(|entry| entry.id == id))
+    }
+
+    /// Applies `change` to the entry with `id`. Returns false when there is no such entry.
+    pub fn update(&self, id: &str, change: impl FnOnce(&mut HistoryEntry)) -> Result<bool, Box<dyn Error>> {
+        self.locked(|| {
+            let mut entries = self.load()?;
+            let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) else {
+                return Ok(false);
+            };
+            change(entry);
+            self.rewrite(&entries)?;
+            Ok(true)
+        })
+    }
+
+    /// Returns false when there is no entry with `id`.
+    pub fn delete(&self, id: &str) -> Result<bool, Box<dyn Error>> {
+        self.locked(|| {
+            let mut entries = self.load()?;
+            let before = entries.len();
+            entries.retain(|entry| entry.id != id);
+            if entries.len() == before {
+                return Ok(false);
+            }
+            self.rewrite(&entries)?;
+            Ok(true)
+        })
+    }
+
+    /// Runs `f` holding the history lock, so a rewrite can't drop an entry
+    /// appended or changed in between by another process.
+    fn locked<T>(&self, f: impl FnOnce() -> Result<T, Box<dyn Error>>) -> Result<T, Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // The lock lives in its own file because the history is replaced on every rewrite
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("jsonl.lock"))?;
+        lock.lock_exclusive()?;
+        let result = f();
+        lock.unlock()?;
+        result
+    }
+
+    fn rewrite(&self, entries: &[HistoryEntry]) -> Result<(), Box<dyn Error>> {
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&temp_path, content)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600))?;
+        }
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(temp_dir: &TempDir) -> HistoryStore {
+        HistoryStore::new(temp_dir.path().join(".vocal/history.jsonl"))
+    }
+
+    fn entry(id: &str, raw: &str, refined: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            refined_prompt: refined.map(str::to_string),
+            ..HistoryEntry::new(raw)
+        }
+    }
+
+    #[test]
+    fn test_list_is_newest_first_and_paged() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+        assert!(store.list(10, 0).unwrap().is_empty());
+
+        for id in ["a", "b", "c"] {
+            store.append(&entry(id, id, None)).unwrap();
+        }
+
+        let ids = |entries: Vec<HistoryEntry>| entries.into_iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(store.list(10, 0).unwrap()), ["c", "b", "a"]);
+        assert_eq!(ids(store.list(1, 1).unwrap()), ["b"]);
+    }
+
+    #[test]
+    fn test_search_matches_transcript_and_prompt() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+        store.append(&entry("a", "run the tests", Some("Run the test suite and fix failures."))).unwrap();
+        store.append(&entry("b", "update the readme", None)).unwrap();
+
+        assert_eq!(store.search("FAILURES", 10).unwrap()[0].id, "a");
+        assert_eq!(store.search("readme", 10).unwrap()[0].id, "b");
+        assert!(store.search("deploy", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_update_and_delete() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+        store.append(&entry("a", "first", None)).unwrap();
+        store.append(&entry("b", "second", None)).unwrap();
+
+        assert!(store
+            .update("a", |e| {
+                e.refined_prompt = Some("First.".to_string());
+                e.refinement_ms = Some(420);
+            })
+            .unwrap());
+        assert!(!store.update("missing", |_| {}).unwrap());
+        assert_eq!(store.get("a").unwrap().unwrap().refinement_ms, Some(420));
+
+        assert!(store.delete("a").unwrap());
+        assert!(!store.delete("a").unwrap());
+        assert_eq!(store.load().unwrap().len(), 1);
+        assert!(!store.path().with_extension("jsonl.tmp").exists());
+    }
+
+    #[test]
+    fn test_concurrent_writes_are_not_lost() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(".vocal/history.jsonl");
+        for i in 0..8 {
+            HistoryStore::new(path.clone()).append(&entry(&format!("old{}", i), "old", None)).unwrap();
+        }
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let store = HistoryStore::new(path);
+                    store.append(&entry(&format!("new{}", i), "new", None)).unwrap();
+                    assert!(store.delete(&format!("old{}", i)).unwrap());
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut ids: Vec<String> = HistoryStore::new(path).load().unwrap().into_iter().map(|e| e.id).collect();
+        ids.sort();
+        assert_eq!(ids, (0..8).map(|i| format!("new{}", i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_recent_prompts_skip_unrefined_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+        store.append(&entry("a", "one", Some("First prompt."))).unwrap();
+        store.append(&entry("b", "two", Some("Second prompt."))).unwrap();
+        store.append(&entry("c", "three", None)).unwrap();
+
+        assert_eq!(store.recent_prompts(10).unwrap().len(), 2);
+        assert_eq!(store.recent_prompt(1).unwrap().as_deref(), Some("Second prompt."));
+        assert_eq!(store.recent_prompt(2).unwrap().as_deref(), Some("First prompt."));
+        assert_eq!(store.recent_prompt(3).unwrap(), None);
+        assert_eq!(store.recent_prompt(0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("Run the tests.", 20), "Run the tests.");
+        assert_eq!(preview("Run the tests and fix any failures.", 12), "Run the tes…");
+        assert_eq!(preview("Run the tests.\nThen commit.", 40), "Run the tests.…");
+    }
+
+    #[test]
+    fn test_summary() {
+        let entry = HistoryEntry {
+            target_session: Some("abc123".to_string()),
+            transcription_provider: Some("Groq".to_string()),
+            transcription_ms: Some(310),
+            ..entry("a", "run the tests", Some("Run the tests.\nFix failures."))
+        };
+
+        let summary = entry.summary();
+        assert!(summary.starts_with("a  "));
+        assert!(summary.contains("  session: abc123\n"));
+        assert!(summary.contains("  prompt:  Run the tests.\n           Fix failures.\n"));
+        assert!(summary.contains("  timing:  Groq 310ms\n"));
+    }
+
+    #[test]
+    fn test_corrupt_lines_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+        store.append(&entry("a", "first", None)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(store.path()).unwrap();
+        writeln!(file, "{{\"id\": \"trunc").unwrap();
+
+        assert_eq!(store.load().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,415 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use crate::hooks::{HookContext, PermissionLevel, PostToolUse, ToolUse};
+
+/// The current log is rotated once it grows past this size.
+pub const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated logs kept besides the current one; older ones are deleted.
+pub const MAX_ROTATED_LOGS: usize = 5;
+
+pub fn audit_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal")
+        .join("audit")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditEvent {
+    /// PreToolUse: what hands-free mode let Claude do
+    Decision,
+    /// PostToolUse: how the tool call went
+    Execution,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub event: AuditEvent,
+    pub session_id: String,
+    pub cwd: String,
+    pub tool_name: String,
+    /// SHA-256 of the tool input, which pairs a decision with its execution
+    /// without copying file contents into the log
+    pub input_digest: String,
+    pub summary: String,
+    /// The policy rule, or the built-in rules, that judged the call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision: Option<PermissionLevel>,
+    /// Who settled a call the rules wanted validated, e.g. "approved by voice"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validated_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<AuditOutcome>,
+}
+
+impl AuditEntry {
+    fn new(ctx: &HookContext, event: AuditEvent, tool_use: &ToolUse) -> Self {
+        AuditEntry {
+            timestamp: Utc::now(),
+            event,
+            session_id: ctx.session_id.clone(),
+            cwd: ctx.cwd.clone(),
+            tool_name: tool_use.tool_name.clone(),
+            input_digest: input_digest(&tool_use.tool_input),
+            summary: tool_use.summary(),
+            rule: None,
+            decision: None,
+            validated_by: None,
+            outcome: None,
+        }
+    }
+
+    /// The decision taken for a tool call: `rule` is what the safety rules
+    /// said, `validated_by` names whoever overrode them.
+    pub fn decision(
+        ctx: &HookContext,
+        tool_use: &ToolUse,
+        rule: &str,
+        decision: PermissionLevel,
+        validated_by: Option<String>,
+    ) -> Self {
+        AuditEntry {
+            rule: Some(rule.to_string()),
+            decision: Some(decision),
+            validated_by,
+            ..Self::new(ctx, AuditEvent::Decision, tool_use)
+        }
+    }
+
+    pub fn execution(ctx: &HookContext, post: &PostToolUse) -> Self {
+        AuditEntry {
+            outcome: Some(outcome_of(&post.tool_response)),
+            ..Self::new(ctx, AuditEvent::Execution, &post.tool_use())
+        }
+    }
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match (self.decision, self.outcome) {
+            (Some(decision), _) => decision.as_str(),
+            (None, Some(AuditOutcome::Success)) => "success",
+            (None, Some(AuditOutcome::Failure)) => "failure",
+            (None, None) => "-",
+        };
+        write!(
+            f,
+            "{}  {:<8}  {:<8}  {:<12}  {}",
+            self.timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            self.session_id.chars().take(8).collect::<String>(),
+            result,
+            self.tool_name,
+            self.summary
+        )?;
+        match (&self.validated_by, &self.rule) {
+            (Some(by), Some(rule)) => write!(f, "  [{}; rules said: {}]", by, rule),
+            (None, Some(rule)) => write!(f, "  [{}]", rule),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn input_digest(tool_input: &Value) -> String {
+    sha256_hex(tool_input.to_string().as_bytes())
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Claude Code reports most failures as a response flag rather than a separate event.
+fn outcome_of(tool_response: &Value) -> AuditOutcome {
+    let failed = tool_response["success"].as_bool() == Some(false)
+        || tool_response["interrupted"].as_bool() == Some(true)
+        || tool_response["is_error"].as_bool() == Some(true)
+        || tool_response.get("error").is_some_and(|error| !error.is_null());
+    if failed {
+        AuditOutcome::Failure
+    } else {
+        AuditOutcome::Success
+    }
+}
+
+/// Filters for `vocal audit query`; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    /// A session id or a prefix of one
+    pub session: Option<String>,
+    /// Tool name, case-insensitive
+    pub tool: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.session.as_deref().is_none_or(|session| entry.session_id.starts_with(session))
+            && self.tool.as_deref().is_none_or(|tool| entry.tool_name.eq_ignore_ascii_case(tool))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+    }
+}
+
+/// Parses `--since`: a duration back from `now` (`30m`, `2h`, `7d`, `1w`), a
+/// local date (`2026-10-17`) or an RFC 3339 timestamp.
+pub fn parse_since(text: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let text = text.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(text) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+            .map(|midnight| midnight.with_timezone(&Utc))
+            .ok_or_else(|| format!("No local midnight on {}", text));
+    }
+
+    let invalid = || format!("Invalid time '{}': use e.g. 30m, 2h, 7d, 2026-10-17 or an RFC 3339 timestamp", text);
+    let split = text.len().checked_sub(1).filter(|&split| text.is_char_boundary(split)).ok_or_else(invalid)?;
+    let (amount, unit) = text.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let duration = match unit {
+        "s" => Duration::seconds(amount),
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => return Err(invalid()),
+    };
+    Ok(now - duration)
+}
+
+/// Append-only JSONL log of hook decisions and tool executions, rotated by
+/// size into `audit.1.jsonl` (newest) to `audit.5.jsonl` (oldest).
+pub struct AuditLog {
+    dir: PathBuf,
+}
+
+impl AuditLog {
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        AuditLog { dir: dir.into() }
+    }
+
+    pub fn open() -> Self {
+        Self::at(audit_dir())
+    }
+
+    /// The log currently written to.
+    pub fn path(&self) -> PathBuf {
+        self.dir.join("audit.jsonl")
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        self.dir.join(format!("audit.{}.jsonl", n))
+    }
+
+    /// Appends `entry`; hooks of concurrent sessions share the log, so
+    /// rotation and the write happen under a lock.
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&self.dir)?;
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join("audit.lock"))?;
+        lock.lock_exclusive()?;
+
+        let path = self.path();
+        if fs::metadata(&path).is_ok_and(|metadata| metadata.len() >= MAX_LOG_BYTES) {
+            self.rotate()?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+
+        lock.unlock()?;
+        Ok(())
+    }
+
+    fn rotate(&self) -> Result<(), Box<dyn Error>> {
+        let oldest = self.rotated_path(MAX_ROTATED_LOGS);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for n in (1..MAX_ROTATED_LOGS).rev() {
+            let from = self.rotated_path(n);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(n + 1))?;
+            }
+        }
+        fs::rename(self.path(), self.rotated_path(1))?;
+        Ok(())
+    }
+
+    /// All entries, oldest first, across the rotated logs. Lines that fail to
+    /// parse are skipped like in the dictation history.
+    pub fn load(&self) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+        let mut paths: Vec<PathBuf> = (1..=MAX_ROTATED_LOGS).rev().map(|n| self.rotated_path(n)).collect();
+        paths.push(self.path());
+
+        let mut entries = Vec::new();
+        for path in paths.iter().filter(|path| path.exists()) {
+            read_entries(path, &mut entries)?;
+        }
+        Ok(entries)
+    }
+
+    /// The last `limit` entries matching `query`, oldest first.
+    pub fn query(&self, query: &AuditQuery, limit: usize) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+        let mut entries: Vec<AuditEntry> = self.load()?.into_iter().filter(|entry| query.matches(entry)).collect();
+        let skip = entries.len().saturating_sub(limit);
+        entries.drain(..skip);
+        Ok(entries)
+    }
+
+    pub fn tail(&self, limit: usize) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+        self.query(&AuditQuery::default(), limit)
+    }
+}
+
+fn read_entries(path: &Path, entries: &mut Vec<AuditEntry>) -> Result<(), Box<dyn Error>> {
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("Skipping unreadable audit line in {}: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::HookEvent;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn context(session_id: &str, event: Value) -> HookContext {
+        let mut payload = json!({
+            "session_id": session_id,
+            "transcript_path": "/tmp/transcript.jsonl",
+            "cwd": "/work/api",
+            "tool_name": "Bash",
+            "tool_input": {"command": "cargo test", "description": "the tests"}
+        });
+        payload.as_object_mut().unwrap().extend(event.as_object().unwrap().clone());
+        serde_json::from_value(payload).unwrap()
+    }
+
+    fn bash(command: &str) -> ToolUse {
+        ToolUse { tool_name: "Bash".to_string(), tool_input: json!({ "command": command }) }
+    }
+
+    #[test]
+    fn test_entries_pair_decision_and_execution() {
+        let ctx = context("a1", json!({"hook_event_name": "PreToolUse"}));
+        let HookEvent::PreToolUse(tool_use) = &ctx.event else { unreachable!() };
+        let decision = AuditEntry::decision(&ctx, tool_use, "built-in safety rules", PermissionLevel::Allow, None);
+
+        let ctx = context("a1", json!({"hook_event_name": "PostToolUse", "tool_response": {"interrupted": true}}));
+        let HookEvent::PostToolUse(post) = &ctx.event else { unreachable!() };
+        let execution = AuditEntry::execution(&ctx, post);
+
+        assert_eq!(decision.input_digest, execution.input_digest);
+        assert_eq!(decision.input_digest.len(), 64);
+        assert_eq!(execution.outcome, Some(AuditOutcome::Failure));
+        assert!(decision.to_string().ends_with("a1        allow     Bash          Run the tests: cargo test  [built-in safety rules]"));
+
+        let json = serde_json::to_value(&decision).unwrap();
+        assert_eq!(json["decision"], "allow");
+        assert!(json.get("outcome").is_none());
+    }
+
+    #[test]
+    fn test_log_rotates_and_keeps_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = AuditLog::at(temp_dir.path());
+        let ctx = context("a1", json!({"hook_event_name": "PreToolUse"}));
+
+        // Pad the current log past the limit so the next append rotates it
+        log.append(&AuditEntry::decision(&ctx, &bash("first"), "rule", PermissionLevel::Allow, None)).unwrap();
+        let padding = OpenOptions::new().append(true).open(log.path()).unwrap();
+        padding.set_len(MAX_LOG_BYTES).unwrap();
+        log.append(&AuditEntry::decision(&ctx, &bash("second"), "rule", PermissionLevel::Block, None)).unwrap();
+
+        assert!(temp_dir.path().join("audit.1.jsonl").exists());
+        let summaries: Vec<String> = log.load().unwrap().into_iter().map(|entry| entry.summary).collect();
+        assert_eq!(summaries, ["Run first", "Run second"]);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = AuditLog::at(temp_dir.path());
+        let now = Utc::now();
+
+        let a = context("session-a", json!({"hook_event_name": "PreToolUse"}));
+        let b = context("session-b", json!({"hook_event_name": "PreToolUse"}));
+        let mut old = AuditEntry::decision(&a, &bash("old"), "rule", PermissionLevel::Allow, None);
+        old.timestamp = now - Duration::days(2);
+        let read = ToolUse { tool_name: "Read".to_string(), tool_input: json!({"file_path": "/work/api/a.rs"}) };
+        for entry in [
+            old,
+            AuditEntry::decision(&a, &bash("new"), "rule", PermissionLevel::Allow, None),
+            AuditEntry::decision(&a, &read, "rule", PermissionLevel::Allow, None),
+            AuditEntry::decision(&b, &bash("other"), "rule", PermissionLevel::Validate, Some("denied by voice".to_string())),
+        ] {
+            log.append(&entry).unwrap();
+        }
+
+        let query = AuditQuery {
+            session: Some("session-a".to_string()),
+            tool: Some("bash".to_string()),
+            since: Some(parse_since("1d", now).unwrap()),
+        };
+        let summaries: Vec<String> = log.query(&query, 10).unwrap().into_iter().map(|entry| entry.summary).collect();
+        assert_eq!(summaries, ["Run new"]);
+
+        assert_eq!(log.tail(2).unwrap()[1].validated_by.as_deref(), Some("denied by voice"));
+        assert_eq!(log.query(&AuditQuery { session: Some("session".to_string()), ..Default::default() }, 10).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("90m", now).unwrap(), now - Duration::minutes(90));
+        assert_eq!(parse_since("1w", now).unwrap(), now - Duration::days(7));
+        assert_eq!(
+            parse_since("2026-10-17T08:00:00Z", now).unwrap(),
+            DateTime::parse_from_rfc3339("2026-10-17T08:00:00Z").unwrap()
+        );
+        assert!(parse_since("2026-10-17", now).is_ok());
+        assert!(parse_since("yesterday", now).is_err());
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("5é", now).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use crate::hooks::state;
+use crate::hooks::transcript::TranscriptEntry;
+
+/// Runaway-loop limits for hands-free mode, the `[handsFree]` table of
+/// `~/.vocal/config.toml`. A run is a series of Stop→prompt cycles in one
+/// session without a pause longer than `idle_reset_minutes`; `0` disables a limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HandsFreeConfig {
+    pub max_cycles: u32,
+    pub max_run_minutes: u64,
+    pub max_run_tokens: u64,
+    pub idle_reset_minutes: u64,
+}
+
+impl Default for HandsFreeConfig {
+    fn default() -> Self {
+        HandsFreeConfig {
+            max_cycles: 10,
+            max_run_minutes: 30,
+            max_run_tokens: 2_000_000,
+            idle_reset_minutes: 5,
+        }
+    }
+}
+
+impl HandsFreeConfig {
+    /// Reads only the `[handsFree]` table so the hook binary doesn't depend
+    /// on the rest of the app configuration; falls back to defaults.
+    pub fn load() -> Self {
+        Self::load_from(&state::config_toml_path()).unwrap_or_else(|e| {
+            eprintln!("Warning: Using default hands-free limits: {}", e);
+            HandsFreeConfig::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn Error>> {
+        state::load_config_table(path, "handsFree")
+    }
+}
+
+/// The current run of one session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycleRun {
+    pub cycles: u32,
+    pub tokens: u64,
+    pub started_at: i64,
+    pub last_cycle_at: i64,
+    /// How far the session transcript has been read for token usage
+    pub transcript_offset: u64,
+    /// The assistant message read last, which may continue past the offset
+    #[serde(default)]
+    pub last_message_id: Option<String>,
+    /// When the prompt of the current turn was submitted
+    #[serde(default)]
+    pub last_prompt_at: Option<i64>,
+}
+
+impl CycleRun {
+    /// Why the run must stop, if it exceeded any limit.
+    pub fn exceeded(&self, limits: &HandsFreeConfig) -> Option<String> {
+        let minutes = (self.last_cycle_at - self.started_at).max(0) as u64 / 60;
+        if limits.max_cycles > 0 && self.cycles >= limits.max_cycles {
+            Some(format!("{} hands-free cycles in a row (limit {})", self.cycles, limits.max_cycles))
+        } else if limits.max_run_minutes > 0 && minutes >= limits.max_run_minutes {
+            Some(format!("hands-free run lasted {} minutes (limit {})", minutes, limits.max_run_minutes))
+        } else if limits.max_run_tokens > 0 && self.tokens >= limits.max_run_tokens {
+            Some(format!("hands-free run used {} tokens (limit {})", self.tokens, limits.max_run_tokens))
+        } else {
+            None
+        }
+    }
+}
+
+/// Per-session cycle runs in `~/.vocal/cycles.json`.
+pub struct CycleTracker {
+    path: PathBuf,
+}
+
+impl CycleTracker {
+    pub fn open() -> Self {
+        let dir = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join(".vocal");
+        Self::at(dir.join("cycles.json"))
+    }
+
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        CycleTracker { path: path.into() }
+    }
+
+    /// Notes when a prompt of `session_id` was submitted; the pause before it
+    /// decides whether the turn continues the run.
+    pub fn record_prompt(&self, session_id: &str) -> Result<(), Box<dyn Error>> {
+        let now = Utc::now().timestamp();
+        state::update_locked_json(&self.path, |runs: &mut BTreeMap<String, CycleRun>| {
+            runs.entry(session_id.to_string()).or_default().last_prompt_at = Some(now);
+        })
+    }
+
+    /// Counts a finished cycle of `session_id` and the tokens its transcript
+    /// gained since the last one. A new run starts when the prompt of this
+    /// turn came long after the previous Stop; Claude's working time is not
+    /// a pause.
+    pub fn record_cycle(
+        &self,
+        session_id: &str,
+        transcript_path: &Path,
+        limits: &HandsFreeConfig,
+    ) -> Result<CycleRun, Box<dyn Error>> {
+        let now = Utc::now().timestamp();
+        state::update_locked_json(&self.path, |runs: &mut BTreeMap<String, CycleRun>| {
+            let run = runs.entry(session_id.to_string()).or_default();
+            let idle_secs = (limits.idle_reset_minutes * 60) as i64;
+            // Without a recorded prompt the turn is taken to have just started
+            let prompted_at = run.last_prompt_at.filter(|at| *at >= run.last_cycle_at).unwrap_or(now);
+            if run.cycles == 0 || prompted_at - run.last_cycle_at > idle_secs {
+                *run = CycleRun {
+                    started_at: prompted_at,
+                    transcript_offset: run.transcript_offset,
+                    last_message_id: run.last_message_id.take(),
+                    ..CycleRun::default()
+                };
+            }
+
+            match read_token_usage(transcript_path, run.transcript_offset, run.last_message_id.as_deref()) {
+                Ok(read) => {
+                    run.tokens += read.tokens;
+                    run.transcript_offset = read.offset;
+                    run.last_message_id = read.last_message_id.or(run.last_message_id.take());
+                }
+                Err(e) => eprintln!("Warning: Failed to read token usage: {}", e),
+            }
+            run.cycles += 1;
+            run.last_cycle_at = now;
+            run.clone()
+        })
+    }
+
+    pub fn get(&self, session_id: &str) -> Result<Option<CycleRun>, Box<dyn Error>> {
+        state::update_locked_json(&self.path, |runs: &mut BTreeMap<String, CycleRun>| {
+            runs.get(session_id).cloned()
+        })
+    }
+
+    /// Forgets every run, e.g. when hands-free mode is switched on again.
+    pub fn reset(&self) -> Result<(), Box<dyn Error>> {
+        state::update_locked_json(&self.path, |runs: &mut BTreeMap<String, CycleRun>| runs.clear())
+    }
+}
+
+struct TokenRead {
+    tokens: u64,
+    /// The end of the last full line
+    offset: u64,
+    last_message_id: Option<String>,
+}
+
+/// Sums the input and output tokens of the assistant messages after byte
+/// `offset`. Claude Code repeats a message's usage on each of its lines, so
+/// every message id counts once; `last_message_id` was counted by the
+/// previous read.
+fn read_token_usage(path: &Path, offset: u64, last_message_id: Option<&str>) -> Result<TokenRead, Box<dyn Error>> {
+    let mut file = File::open(path)?;
+    // A transcript that shrank was replaced, so read it from the start
+    let offset = if file.metadata()?.len() < offset { 0 } else { offset };
+    file.seek(SeekFrom::Start(offset))?;
+
+    let mut reader = BufReader::new(file);
+    let mut tokens = 0;
+    let mut position = offset;
+    let mut counted: HashSet<String> = last_message_id.map(str::to_string).into_iter().collect();
+    let mut last_message_id = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        // Stop before a line Claude Code is still writing
+        if read == 0 || !line.ends_with('\n') {
+            break;
+        }
+        position += read as u64;
+
+        let Ok(TranscriptEntry::Assistant(entry)) = serde_json::from_str(&line) else {
+            continue;
+        };
+        let Some(usage) = entry.message.usage else {
+            continue;
+        };
+        match entry.message.id {
+            Some(id) if !counted.insert(id.clone()) => {}
+            id => {
+                tokens += usage.total();
+                last_message_id = id.or(last_message_id);
+            }
+        }
+    }
+    Ok(TokenRead { tokens, offset: position, last_message_id })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn append_usage(path: &Path, id: &str, input: u64, output: u64) {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path).unwrap();
+        let entry = serde_json::json!({
+            "type": "assistant",
+            "message": {"id": id, "role": "assistant", "usage": {"input_tokens": input, "output_tokens": output}}
+        });
+        writeln!(file, "{}", entry).unwrap();
+    }
+
+    #[test]
+    fn test_cycles_and_tokens_accumulate() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker = CycleTracker::at(temp_dir.path().join("cycles.json"));
+        let transcript = temp_dir.path().join("transcript.jsonl");
+        let limits = HandsFreeConfig { max_run_tokens: 1000, ..HandsFreeConfig::default() };
+
+        append_usage(&transcript, "msg_1", 100, 50);
+        let run = tracker.record_cycle("a", &transcript, &limits).unwrap();
+        assert_eq!((run.cycles, run.tokens), (1, 150));
+        assert_eq!(run.exceeded(&limits), None);
+
+        // Only the new transcript entries count towards the next cycle
+        append_usage(&transcript, "msg_2", 600, 300);
+        let run = tracker.record_cycle("a", &transcript, &limits).unwrap();
+        assert_eq!((run.cycles, run.tokens), (2, 1050));
+        assert!(run.exceeded(&limits).unwrap().contains("1050 tokens"));
+
+        // Sessions are tracked separately
+        let other = tracker.record_cycle("b", &temp_dir.path().join("missing.jsonl"), &limits).unwrap();
+        assert_eq!((other.cycles, other.tokens), (1, 0));
+        assert_eq!(tracker.get("a").unwrap().unwrap().cycles, 2);
+
+        tracker.reset().unwrap();
+        assert!(tracker.get("a").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_repeated_message_usage_counts_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracker = CycleTracker::at(temp_dir.path().join("cycles.json"));
+        let transcript = temp_dir.path().join("transcript.jsonl");
+        let limits = HandsFreeConfig::default();
+
+        // One message per content block, e.g. text and two tool calls
+        for _ in 0..3 {
+            append_usage(&transcript, "msg_1", 100, 50);
+        }
+        assert_eq!(tracker.record_cycle("a", &transcript, &limits).unwrap().tokens, 150);
+
+        // The last message may go on after the previous read
+        append_usage(&transcript, "msg_1", 100, 50);
+        append_usage(&transcript, "msg_2", 10, 5);
+        append_usage(&transcript, "msg_2", 10, 5);
+        assert_eq!(tracker.record_cycle("a", &transcript, &limits).unwrap().tokens, 165);
+    }
+
+    #[test]
+    fn test_long_turn_continues_the_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("cycles.json");
+        let now = Utc::now().timestamp();
+        let limits = HandsFreeConfig::default();
+        // Prompted a minute after the last Stop, then Claude worked for 20 minutes
+        let working = CycleRun {
+            cycles: 4,
+            started_at: now - 40 * 60,
+            last_cycle_at: now - 21 * 60,
+            last_prompt_at: Some(now - 20 * 60),
+            ..CycleRun::default()
+        };
+        fs::write(&path, serde_json::to_string(&BTreeMap::from([("a".to_string(), working)])).unwrap()).unwrap();
+
+        let tracker = CycleTracker::at(&path);
+        let run = tracker.record_cycle("a", &temp_dir.path().join("missing.jsonl"), &limits).unwrap();
+        assert_eq!(run.cycles, 5);
+        assert!(run.exceeded(&limits).unwrap().contains("40 minutes"));
+
+        // A prompt long after that Stop starts over
+        let mut runs: BTreeMap<String, CycleRun> = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        runs.get_mut("a").unwrap().last_cycle_at = now - 10 * 60;
+        fs::write(&path, serde_json::to_string(&runs).unwrap()).unwrap();
+        tracker.record_prompt("a").unwrap();
+        let run = tracker.record_cycle("a", &temp_dir.path().join("missing.jsonl"), &limits).unwrap();
+        assert_eq!(run.cycles, 1);
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = HandsFreeConfig::default();
+        let now = Utc::now().timestamp();
+        let run = CycleRun { cycles: 3, started_at: now - 600, last_cycle_at: now, ..CycleRun::default() };
+        assert_eq!(run.exceeded(&limits), None);
+
+        let many = CycleRun { cycles: 10, ..run.clone() };
+        assert!(many.exceeded(&limits).unwrap().contains("10 hands-free cycles"));
+
+        let long = CycleRun { started_at: now - 31 * 60, ..run.clone() };
+        assert!(long.exceeded(&limits).unwrap().contains("31 minutes"));
+
+        let unlimited = HandsFreeConfig { max_cycles: 0, ..limits };
+        assert_eq!(many.exceeded(&unlimited), None);
+    }
+
+    #[test]
+    fn test_pause_starts_a_new_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("cycles.json");
+        let stale = CycleRun {
+            cycles: 9,
+            tokens: 5000,
+            started_at: 0,
+            last_cycle_at: Utc::now().timestamp() - 6 * 60,
+            transcript_offset: 0,
+            last_message_id: None,
+            last_prompt_at: None,
+        };
+        fs::write(&path, serde_json::to_string(&BTreeMap::from([("a".to_string(), stale)])).unwrap()).unwrap();
+
+        let run = CycleTracker::at(&path)
+            .record_cycle("a", &temp_dir.path().join("missing.jsonl"), &HandsFreeConfig::default())
+            .unwrap();
+        assert_eq!((run.cycles, run.tokens), (1, 0));
+    }
+
+    #[test]
+    fn test_config_section() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "hotkey = \"F5\"\n\n[handsFree]\nmaxCycles = 3\n").unwrap();
+
+        let config = HandsFreeConfig::load_from(&path).unwrap();
+        assert_eq!(config.max_cycles, 3);
+        assert_eq!(config.max_run_minutes, 30);
+        assert_eq!(HandsFreeConfig::load_from(&temp_dir.path().join("none.toml")).unwrap(), HandsFreeConfig::default());
+    }
+}
@@ -0,0 +1,388 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::error::Error;
+use std::io::{self, Read};
+
+/// A hook invocation as Claude Code sends it on stdin: the fields every
+/// event carries plus the event itself, tagged by `hook_event_name`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HookContext {
+    pub session_id: String,
+    pub transcript_path: String,
+    #[serde(default)]
+    pub cwd: String,
+    #[serde(flatten)]
+    pub event: HookEvent,
+}
+
+impl HookContext {
+    pub fn from_stdin() -> Result<Self, Box<dyn Error>> {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        Self::from_json(&buffer)
+    }
+
+    pub fn from_json(payload: &str) -> Result<Self, Box<dyn Error>> {
+        if payload.trim().is_empty() {
+            return Err("no hook payload on stdin".into());
+        }
+        serde_json::from_str(payload).map_err(|e| format!("malformed hook payload: {}", e).into())
+    }
+
+    pub fn hook_event_name(&self) -> &'static str {
+        self.event.name()
+    }
+
+    /// Error for a handler that was invoked with another event's payload.
+    pub fn unexpected_event(&self, expected: &str) -> Box<dyn Error> {
+        format!("expected a {} hook payload, got {}", expected, self.hook_event_name()).into()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "hook_event_name")]
+pub enum HookEvent {
+    PreToolUse(ToolUse),
+    PostToolUse(PostToolUse),
+    Stop(Stop),
+    SubagentStop(Stop),
+    UserPromptSubmit(UserPromptSubmit),
+    Notification(Notification),
+    PreCompact(PreCompact),
+    SessionStart(SessionStart),
+    SessionEnd(SessionEnd),
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::PreToolUse(_) => "PreToolUse",
+            HookEvent::PostToolUse(_) => "PostToolUse",
+            HookEvent::Stop(_) => "Stop",
+            HookEvent::SubagentStop(_) => "SubagentStop",
+            HookEvent::UserPromptSubmit(_) => "UserPromptSubmit",
+            HookEvent::Notification(_) => "Notification",
+            HookEvent::PreCompact(_) => "PreCompact",
+            HookEvent::SessionStart(_) => "SessionStart",
+            HookEvent::SessionEnd(_) => "SessionEnd",
+        }
+    }
+}
+
+/// Commands longer than this are cut in summaries.
+const MAX_COMMAND_CHARS: usize = 120;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ToolUse {
+    pub tool_name: String,
+    pub tool_input: Value,
+}
+
+impl ToolUse {
+    /// The tool's input parsed into its typed form; tools without a typed
+    /// form keep their raw JSON.
+    pub fn input(&self) -> Result<ToolInput, serde_json::Error> {
+        let input = self.tool_input.clone();
+        Ok(match self.tool_name.as_str() {
+            "Bash" => ToolInput::Bash(serde_json::from_value(input)?),
+            "Edit" => ToolInput::Edit(serde_json::from_value(input)?),
+            "Write" => ToolInput::Write(serde_json::from_value(input)?),
+            "MultiEdit" => ToolInput::MultiEdit(serde_json::from_value(input)?),
+            "Read" => ToolInput::Read(serde_json::from_value(input)?),
+            _ => ToolInput::Other(input),
+        })
+    }
+
+    /// One line describing the call, short enough to be read out loud.
+    /// Long commands are cut, see [`ToolUse::summary_is_truncated`].
+    pub fn summary(&self) -> String {
+        match self.input() {
+            Ok(ToolInput::Bash(bash)) => {
+                let mut command: String = bash.command.chars().take(MAX_COMMAND_CHARS).collect();
+                if command.len() < bash.command.len() {
+                    command.push('…');
+                }
+                match bash.description {
+                    Some(description) => format!("Run {}: {}", description, command),
+                    None => format!("Run {}", command),
+                }
+            }
+            Ok(ToolInput::Edit(edit)) => format!("Edit {}", edit.file_path),
+            Ok(ToolInput::MultiEdit(edit)) => format!("Make {} edits to {}", edit.edits.len(), edit.file_path),
+            Ok(ToolInput::Write(write)) => format!("Write {}", write.file_path),
+            Ok(ToolInput::Read(read)) => format!("Read {}", read.file_path),
+            Ok(ToolInput::Other(_)) | Err(_) => format!("Use the {} tool", self.tool_name),
+        }
+    }
+
+    /// Whether [`ToolUse::summary`] leaves part of the command out.
+    pub fn summary_is_truncated(&self) -> bool {
+        matches!(self.input(), Ok(ToolInput::Bash(bash)) if bash.command.chars().count() > MAX_COMMAND_CHARS)
+    }
+
+    /// The full command or file path the call acts on.
+    pub fn detail(&self) -> Option<String> {
+        match self.input() {
+            Ok(ToolInput::Bash(bash)) => Some(bash.command),
+            Ok(ToolInput::Edit(edit)) => Some(edit.file_path),
+            Ok(ToolInput::MultiEdit(edit)) => Some(edit.file_path),
+            Ok(ToolInput::Write(write)) => Some(write.file_path),
+            Ok(ToolInput::Read(read)) => Some(read.file_path),
+            Ok(ToolInput::Other(_)) | Err(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PostToolUse {
+    pub tool_name: String,
+    pub tool_input: Value,
+    #[serde(default)]
+    pub tool_response: Value,
+}
+
+impl PostToolUse {
+    pub fn tool_use(&self) -> ToolUse {
+        ToolUse {
+            tool_name: self.tool_name.clone(),
+            tool_input: self.tool_input.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum ToolInput {
+    Bash(BashInput),
+    Edit(EditInput),
+    Write(WriteInput),
+    MultiEdit(MultiEditInput),
+    Read(ReadInput),
+    Other(Value),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BashInput {
+    pub command: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Milliseconds
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub run_in_background: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EditInput {
+    pub file_path: String,
+    pub old_string: String,
+    pub new_string: String,
+    #[serde(default)]
+    pub replace_all: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WriteInput {
+    pub file_path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MultiEditInput {
+    pub file_path: String,
+    pub edits: Vec<EditOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EditOperation {
+    pub old_string: String,
+    pub new_string: String,
+    #[serde(default)]
+    pub replace_all: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReadInput {
+    pub file_path: String,
+    #[serde(default)]
+    pub offset: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Stop {
+    /// Set when Claude is already continuing because of a Stop hook
+    #[serde(default)]
+    pub stop_hook_active: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserPromptSubmit {
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Notification {
+    pub message: String,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreCompact {
+    pub trigger: CompactTrigger,
+    #[serde(default)]
+    pub custom_instructions: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompactTrigger {
+    Manual,
+    Auto,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionStart {
+    pub source: SessionStartSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStartSource {
+    Startup,
+    Resume,
+    Clear,
+    Compact,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionEnd {
+    pub reason: SessionEndReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEndReason {
+    Clear,
+    Logout,
+    PromptInputExit,
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn payload(event: Value) -> String {
+        let mut payload = json!({
+            "session_id": "abc123",
+            "transcript_path": "/tmp/transcript.jsonl",
+            "cwd": "/work/project"
+        });
+        payload.as_object_mut().unwrap().extend(event.as_object().unwrap().clone());
+        payload.to_string()
+    }
+
+    #[test]
+    fn test_pre_tool_use_with_typed_input() {
+        let ctx = HookContext::from_json(&payload(json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Bash",
+            "tool_input": {"command": "cargo test", "timeout": 60000}
+        })))
+        .unwrap();
+
+        assert_eq!(ctx.session_id, "abc123");
+        assert_eq!(ctx.cwd, "/work/project");
+        let HookEvent::PreToolUse(tool_use) = &ctx.event else {
+            panic!("expected PreToolUse, got {:?}", ctx.event);
+        };
+        match tool_use.input().unwrap() {
+            ToolInput::Bash(bash) => {
+                assert_eq!(bash.command, "cargo test");
+                assert_eq!(bash.timeout, Some(60000));
+            }
+            other => panic!("expected Bash input, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_typed_inputs() {
+        let multi_edit = ToolUse {
+            tool_name: "MultiEdit".to_string(),
+            tool_input: json!({"file_path": "src/lib.rs", "edits": [{"old_string": "a", "new_string": "b"}]}),
+        };
+        let ToolInput::MultiEdit(input) = multi_edit.input().unwrap() else { panic!() };
+        assert_eq!(input.edits.len(), 1);
+        assert!(!input.edits[0].replace_all);
+
+        let other = ToolUse { tool_name: "Glob".to_string(), tool_input: json!({"pattern": "**/*.rs"}) };
+        assert_eq!(other.input().unwrap(), ToolInput::Other(json!({"pattern": "**/*.rs"})));
+
+        let broken = ToolUse { tool_name: "Write".to_string(), tool_input: json!({"file_path": "a.txt"}) };
+        assert!(broken.input().unwrap_err().to_string().contains("content"));
+    }
+
+    #[test]
+    fn test_summaries() {
+        let tool_use = |tool_name: &str, tool_input: Value| ToolUse { tool_name: tool_name.to_string(), tool_input };
+        assert_eq!(
+            tool_use("Bash", json!({"command": "rm -rf build", "description": "Clean the build"})).summary(),
+            "Run Clean the build: rm -rf build"
+        );
+        assert_eq!(tool_use("Write", json!({"file_path": "/etc/hosts", "content": ""})).summary(), "Write /etc/hosts");
+        assert_eq!(tool_use("WebFetch", json!({"url": "https://example.com"})).summary(), "Use the WebFetch tool");
+
+        assert!(!tool_use("Bash", json!({"command": "rm -rf build"})).summary_is_truncated());
+        assert_eq!(tool_use("Write", json!({"file_path": "/etc/hosts", "content": ""})).detail().as_deref(), Some("/etc/hosts"));
+        assert_eq!(tool_use("WebFetch", json!({"url": "https://example.com"})).detail(), None);
+
+        let long = tool_use("Bash", json!({"command": "x".repeat(500)}));
+        assert!(long.summary().ends_with('…'));
+        assert!(long.summary().chars().count() < 130);
+        assert!(long.summary_is_truncated());
+        assert_eq!(long.detail(), Some("x".repeat(500)));
+    }
+
+    #[test]
+    fn test_every_event_parses() {
+        let events = [
+            json!({"hook_event_name": "PostToolUse", "tool_name": "Read", "tool_input": {}, "tool_response": {"success": true}}),
+            json!({"hook_event_name": "Stop", "stop_hook_active": true}),
+            json!({"hook_event_name": "SubagentStop", "stop_hook_active": false}),
+            json!({"hook_event_name": "UserPromptSubmit", "prompt": "fix the tests"}),
+            json!({"hook_event_name": "Notification", "message": "Claude needs your permission to use Bash"}),
+            json!({"hook_event_name": "PreCompact", "trigger": "auto", "custom_instructions": ""}),
+            json!({"hook_event_name": "SessionStart", "source": "resume"}),
+            json!({"hook_event_name": "SessionStart", "source": "something_new"}),
+            json!({"hook_event_name": "SessionEnd", "reason": "something_new"}),
+        ];
+
+        for event in events {
+            let name = event["hook_event_name"].as_str().unwrap().to_string();
+            let ctx = HookContext::from_json(&payload(event)).unwrap();
+            assert_eq!(ctx.hook_event_name(), name);
+        }
+    }
+
+    #[test]
+    fn test_malformed_payloads_are_errors() {
+        assert_eq!(HookContext::from_json("  \n").unwrap_err().to_string(), "no hook payload on stdin");
+
+        let error = HookContext::from_json("{not json").unwrap_err().to_string();
+        assert!(error.starts_with("malformed hook payload"), "{}", error);
+
+        let missing_tool = payload(json!({"hook_event_name": "PreToolUse", "tool_input": {}}));
+        let error = HookContext::from_json(&missing_tool).unwrap_err().to_string();
+        assert!(error.contains("tool_name"), "{}", error);
+
+        let unknown = payload(json!({"hook_event_name": "Teleport"}));
+        assert!(HookContext::from_json(&unknown).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use chrono::Local;
+use globset::Glob;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::hooks::state;
+
+/// Checkpoints are commits kept alive by refs below this prefix; they never
+/// touch the branch, the index or the stash.
+pub const CHECKPOINT_REFS: &str = "refs/vocal/checkpoints";
+
+/// Git safety for hands-free mode, the `[git]` table of `~/.vocal/config.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GitConfig {
+    /// Checkpoint the working tree at every prompt and guard protected branches
+    pub enabled: bool,
+    /// Branch names or globs on which nothing is auto-approved
+    pub protected_branches: Vec<String>,
+    /// Checkpoints kept per repository; the oldest are deleted first
+    pub max_checkpoints: usize,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig {
+            enabled: false,
+            protected_branches: vec!["main".to_string(), "master".to_string()],
+            max_checkpoints: 50,
+        }
+    }
+}
+
+impl GitConfig {
+    /// Callers fail closed when the config can't be read, see [`guarded_branch`].
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        Self::load_from(&state::config_toml_path())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn Error>> {
+        state::load_config_table(path, "git")
+    }
+
+    pub fn is_protected(&self, branch: &str) -> bool {
+        self.protected_branches.iter().any(|pattern| match Glob::new(pattern) {
+            Ok(glob) => glob.compile_matcher().is_match(branch),
+            Err(_) => pattern == branch,
+        })
+    }
+
+    /// The branch checked out in `cwd` when git safety is on and it is protected.
+    pub fn protected_branch(&self, cwd: &Path) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        current_branch(cwd).filter(|branch| self.is_protected(branch))
+    }
+}
+
+/// The branch in `cwd` on which nothing may be auto-approved. If the config
+/// can't be read, whatever branch is checked out counts as protected.
+pub fn guarded_branch(cwd: &Path) -> Option<String> {
+    match GitConfig::load() {
+        Ok(config) => config.protected_branch(cwd),
+        Err(e) => {
+            eprintln!("Warning: Treating the current branch as protected, the config could not be read: {}", e);
+            current_branch(cwd)
+        }
+    }
+}
+
+/// A checkpoint commit and the ref that keeps it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub reference: String,
+    pub commit: String,
+}
+
+fn git(cwd: &Path, args: &[&str], env: &[(&str, &Path)]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .envs(env.iter().copied())
+        .env("GIT_AUTHOR_NAME", "Vocal")
+        .env("GIT_AUTHOR_EMAIL", "vocal@localhost")
+        .env("GIT_COMMITTER_NAME", "Vocal")
+        .env("GIT_COMMITTER_EMAIL", "vocal@localhost")
+        .output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The branch checked out in `cwd`; `None` outside a repository or on a detached HEAD.
+pub fn current_branch(cwd: &Path) -> Option<String> {
+    git(cwd, &["symbolic-ref", "--short", "-q", "HEAD"], &[])
+        .ok()
+        .filter(|branch| !branch.is_empty())
+}
+
+/// Checkpoint refs of the repository at `cwd`, oldest first.
+pub fn list_checkpoints(cwd: &Path) -> Result<Vec<Checkpoint>, Box<dyn Error>> {
+    let refs = git(cwd, &["for-each-ref", "--sort=refname", "--format=%(refname) %(objectname)", CHECKPOINT_REFS], &[])?;
+    Ok(refs
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(reference, commit)| Checkpoint { reference: reference.to_string(), commit: commit.to_string() })
+        .collect())
+}
+
+/// Commits the whole working tree, untracked files included, under
+/// `refs/vocal/checkpoints/`. Returns `None` outside a repository or when
+/// nothing changed since the last checkpoint. Only checkpoints beyond `max`
+/// are deleted.
+pub fn create_checkpoint(cwd: &Path, message: &str, max: usize) -> Result<Option<Checkpoint>, Box<dyn Error>> {
+    if git(cwd, &["rev-parse", "--is-inside-work-tree"], &[]).ok().as_deref() != Some("true") {
+        return Ok(None);
+    }
+    let tree = snapshot_tree(cwd)?;
+
+    let checkpoints = list_checkpoints(cwd)?;
+    if let Some(latest) = checkpoints.last() {
+        if git(cwd, &["rev-parse", &format!("{}^{{tree}}", latest.commit)], &[])? == tree {
+            return Ok(None);
+        }
+    }
+
+    // A repository without commits yet has no HEAD to build on
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+    let head = git(cwd, &["rev-parse", "-q", "--verify", "HEAD^{commit}"], &[]).ok();
+    if let Some(head) = &head {
+        args.extend(["-p", head.as_str()]);
+    }
+    let commit = git(cwd, &args, &[])?;
+
+    let reference = format!("{}/{}", CHECKPOINT_REFS, Local::now().format("%Y%m%d-%H%M%S-%3f"));
+    git(cwd, &["update-ref", &reference, &commit], &[])?;
+
+    let excess = (checkpoints.len() + 1).saturating_sub(max.max(1));
+    for old in checkpoints.iter().take(excess) {
+        git(cwd, &["update-ref", "-d", &old.reference], &[])?;
+    }
+    Ok(Some(Checkpoint { reference, commit }))
+}
+
+/// Writes the whole working tree, wherever in it `cwd` is, as a tree object
+/// through a copy of the index, so what the user has staged stays as it is.
+/// Each hook gets its own copy since sessions in one repository run concurrently.
+fn snapshot_tree(cwd: &Path) -> Result<String, Box<dyn Error>> {
+    let git_dir = PathBuf::from(git(cwd, &["rev-parse", "--absolute-git-dir"], &[])?);
+    let index = git_dir.join(format!("vocal-checkpoint-index-{}", std::process::id()));
+    let real_index = git_dir.join("index");
+    if real_index.exists() {
+        fs::copy(&real_index, &index)?;
+    }
+    let env = [("GIT_INDEX_FILE", index.as_path())];
+    let tree = git(cwd, &["add", "-A", ":/"], &env).and_then(|_| git(cwd, &["write-tree"], &env));
+    let _ = fs::remove_file(&index);
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn repo() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let cwd = temp_dir.path();
+        fs::write(cwd.join("tracked.txt"), "one\n").unwrap();
+        for args in [&["init", "-q", "-b", "main"][..], &["add", "tracked.txt"], &["commit", "-q", "-m", "initial"]] {
+            git(cwd, args, &[]).unwrap();
+        }
+        temp_dir
+    }
+
+    #[test]
+    fn test_checkpoint_captures_working_tree_without_touching_index() {
+        let temp_dir = repo();
+        let cwd = temp_dir.path();
+        fs::write(cwd.join("tracked.txt"), "two\n").unwrap();
+        fs::write(cwd.join("new.txt"), "untracked\n").unwrap();
+        let status = git(cwd, &["status", "--porcelain"], &[]).unwrap();
+
+        let checkpoint = create_checkpoint(cwd, "Vocal checkpoint", 10).unwrap().unwrap();
+        assert!(checkpoint.reference.starts_with("refs/vocal/checkpoints/"));
+        assert_eq!(git(cwd, &["show", &format!("{}:new.txt", checkpoint.commit)], &[]).unwrap(), "untracked");
+        assert_eq!(git(cwd, &["show", &format!("{}:tracked.txt", checkpoint.commit)], &[]).unwrap(), "two");
+        assert_eq!(git(cwd, &["status", "--porcelain"], &[]).unwrap(), status);
+        assert_eq!(current_branch(cwd).as_deref(), Some("main"));
+
+        // Nothing changed since
+        assert_eq!(create_checkpoint(cwd, "Vocal checkpoint", 10).unwrap(), None);
+    }
+
+    #[test]
+    fn test_checkpoint_from_subdirectory_covers_whole_repository() {
+        let temp_dir = repo();
+        let root = temp_dir.path();
+        let sub = root.join("sub");
+        fs::create_dir(&sub).unwrap();
+        fs::write(sub.join("a.txt"), "v2\n").unwrap();
+        fs::write(root.join("tracked.txt"), "changed\n").unwrap();
+
+        let checkpoint = create_checkpoint(&sub, "Vocal checkpoint", 10).unwrap().unwrap();
+        assert_eq!(git(root, &["show", &format!("{}:sub/a.txt", checkpoint.commit)], &[]).unwrap(), "v2");
+        assert_eq!(git(root, &["show", &format!("{}:tracked.txt", checkpoint.commit)], &[]).unwrap(), "changed");
+        let leftovers = fs::read_dir(root.join(".git")).unwrap().filter_map(Result::ok);
+        assert!(!leftovers.into_iter().any(|entry| entry.file_name().to_string_lossy().starts_with("vocal-checkpoint-index")));
+    }
+
+    #[test]
+    fn test_old_checkpoints_are_pruned() {
+        let temp_dir = repo();
+        let cwd = temp_dir.path();
+        for n in 0..3 {
+            fs::write(cwd.join("tracked.txt"), format!("{}\n", n)).unwrap();
+            create_checkpoint(cwd, "Vocal checkpoint", 2).unwrap().unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let checkpoints = list_checkpoints(cwd).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        let latest = &checkpoints[1].commit;
+        assert_eq!(git(cwd, &["show", &format!("{}:tracked.txt", latest)], &[]).unwrap(), "2");
+
+        let outside = TempDir::new().unwrap();
+        assert_eq!(create_checkpoint(outside.path(), "Vocal checkpoint", 2).unwrap(), None);
+        assert_eq!(current_branch(outside.path()), None);
+    }
+
+    #[test]
+    fn test_protected_branches() {
+        let config = GitConfig { protected_branches: vec!["main".into(), "release/*".into()], ..GitConfig::default() };
+        assert!(config.is_protected("main"));
+        assert!(config.is_protected("release/1.2"));
+        assert!(!config.is_protected("feature/main"));
+
+        let temp_dir = repo();
+        assert_eq!(config.protected_branch(temp_dir.path()), None);
+        let enabled = GitConfig { enabled: true, ..config };
+        assert_eq!(enabled.protected_branch(temp_dir.path()).as_deref(), Some("main"));
+    }
+}
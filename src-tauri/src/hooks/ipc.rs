@@ -0,0 +1,461 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::hooks::{HookContext, PermissionDecision, SafetyDecision, ToolUse, state};
+
+/// How long a hook waits on the app before falling back to the state files.
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long PreToolUse waits for the user to answer in the app; below the
+/// 30 second timeout the hook is installed with.
+pub const DECISION_TIMEOUT: Duration = Duration::from_secs(28);
+
+// JSON-RPC 2.0 error codes
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// The socket the running app listens on, `~/.vocal/vocal.sock`.
+pub fn socket_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal/vocal.sock")
+}
+
+/// A hook event as reported to the app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookReport {
+    pub hook_event_name: String,
+    pub session_id: String,
+    pub cwd: String,
+}
+
+impl HookReport {
+    pub fn from_hook(ctx: &HookContext) -> Self {
+        HookReport {
+            hook_event_name: ctx.hook_event_name().to_string(),
+            session_id: ctx.session_id.clone(),
+            cwd: ctx.cwd.clone(),
+        }
+    }
+}
+
+/// The Stop hook's report of a finished hands-free cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopReport {
+    #[serde(flatten)]
+    pub hook: HookReport,
+    /// Claude's final response, for the app to read out
+    pub last_message: Option<String>,
+}
+
+/// A tool call the safety rules couldn't settle on their own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionRequest {
+    pub session_id: String,
+    pub cwd: String,
+    pub tool_name: String,
+    pub tool_input: Value,
+    /// What the call does, for the user to approve by ear
+    pub summary: String,
+    /// Why the safety rules want the user to validate it
+    pub reason: String,
+}
+
+/// The app's answer to a `decide` call; no decision keeps the hook's own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionReply {
+    pub decision: Option<PermissionDecision>,
+    pub reason: Option<String>,
+}
+
+/// Hands-free state as the app sees it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandsFreeStatus {
+    /// Switched on, even if an emergency stop is holding it
+    pub active: bool,
+    pub emergency_stop: bool,
+    pub stop_reason: Option<String>,
+}
+
+impl HandsFreeStatus {
+    pub fn from_files() -> Self {
+        HandsFreeStatus {
+            active: state::hands_free_flag_path().exists(),
+            emergency_stop: state::is_emergency_stop_active(),
+            stop_reason: state::hands_free_stop_reason(),
+        }
+    }
+
+    /// Whether hooks should act on behalf of hands-free mode.
+    pub fn is_running(&self) -> bool {
+        self.active && !self.emergency_stop
+    }
+
+    /// An emergency stop switches hands-free mode off, and until it is
+    /// cleared every tool call stops Claude.
+    pub fn halts_claude(&self) -> bool {
+        self.emergency_stop
+    }
+}
+
+/// The methods of the hook→app protocol and their params.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum IpcCall {
+    /// Fire-and-forget report of any hook event
+    HookEvent(HookReport),
+    /// The Stop hook finished a hands-free cycle; replaces `~/.vocal-cycle-trigger`
+    RestartRecording(StopReport),
+    /// Returns a `DecisionReply`
+    Decide(DecisionRequest),
+    /// Returns a `HandsFreeStatus`
+    GetState,
+}
+
+/// One JSON-RPC request per line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcRequest {
+    pub jsonrpc: String,
+    pub id: u64,
+    #[serde(flatten)]
+    pub call: IpcCall,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// One JSON-RPC response per line; `id` is null when the request couldn't be read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpcResponse {
+    pub jsonrpc: String,
+    pub id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<IpcError>,
+}
+
+impl IpcResponse {
+    fn success(id: u64, result: Value) -> Self {
+        IpcResponse { jsonrpc: "2.0".to_string(), id: Some(id), result: Some(result), error: None }
+    }
+
+    fn failure(id: Option<u64>, code: i64, message: impl Into<String>) -> Self {
+        let error = IpcError { code, message: message.into() };
+        IpcResponse { jsonrpc: "2.0".to_string(), id, result: None, error: Some(error) }
+    }
+}
+
+/// What the app does for each call.
+pub trait IpcHandler: Send + Sync + 'static {
+    fn handle(&self, call: IpcCall) -> Result<Value, String>;
+}
+
+/// Serves the protocol on a Unix socket, one thread per connection. The
+/// socket file is removed when the server is dropped.
+#[derive(Debug)]
+pub struct IpcServer {
+    path: PathBuf,
+}
+
+impl IpcServer {
+    pub fn start(path: &Path, handler: impl IpcHandler) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if path.exists() {
+            // A socket nobody answers on is left over from a crash
+            if UnixStream::connect(path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("another Vocal instance is listening on {}", path.display()),
+                ));
+            }
+            fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        // Decisions go through here, so only the user may connect
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+
+        let handler = Arc::new(handler);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let handler = Arc::clone(&handler);
+                        thread::spawn(move || {
+                            if let Err(e) = serve_connection(stream, handler.as_ref()) {
+                                eprintln!("IPC connection error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => eprintln!("IPC accept error: {}", e),
+                }
+            }
+        });
+
+        Ok(IpcServer { path: path.to_path_buf() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn serve_connection(stream: UnixStream, handler: &dyn IpcHandler) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = respond(&line, handler);
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn respond(line: &str, handler: &dyn IpcHandler) -> IpcResponse {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => return IpcResponse::failure(None, PARSE_ERROR, format!("parse error: {}", e)),
+    };
+    let id = value.get("id").and_then(Value::as_u64);
+    let request: IpcRequest = match serde_json::from_value(value) {
+        Ok(request) => request,
+        Err(e) => return IpcResponse::failure(id, INVALID_REQUEST, format!("invalid request: {}", e)),
+    };
+    match handler.handle(request.call) {
+        Ok(result) => IpcResponse::success(request.id, result),
+        Err(e) => IpcResponse::failure(Some(request.id), INTERNAL_ERROR, e),
+    }
+}
+
+/// A connection to the running app.
+pub struct IpcClient {
+    writer: UnixStream,
+    reader: BufReader<UnixStream>,
+    next_id: u64,
+}
+
+impl IpcClient {
+    /// Connects to the app, or returns `None` when it isn't running.
+    pub fn connect() -> Option<Self> {
+        Self::connect_to(&socket_path()).ok()
+    }
+
+    pub fn connect_to(path: &Path) -> io::Result<Self> {
+        let writer = UnixStream::connect(path)?;
+        writer.set_read_timeout(Some(CALL_TIMEOUT))?;
+        writer.set_write_timeout(Some(CALL_TIMEOUT))?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(IpcClient { writer, reader, next_id: 1 })
+    }
+
+    pub fn set_timeout(&self, timeout: Duration) -> io::Result<()> {
+        self.writer.set_read_timeout(Some(timeout))
+    }
+
+    pub fn call(&mut self, call: IpcCall) -> Result<Value, Box<dyn Error>> {
+        let request = IpcRequest { jsonrpc: "2.0".to_string(), id: self.next_id, call };
+        self.next_id += 1;
+        writeln!(self.writer, "{}", serde_json::to_string(&request)?)?;
+        self.writer.flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err("the app closed the connection".into());
+        }
+        let response: IpcResponse = serde_json::from_str(&line)?;
+        if response.id != Some(request.id) {
+            return Err(format!("response id {:?} doesn't match request {}", response.id, request.id).into());
+        }
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(format!("app error {}: {}", error.code, error.message).into()),
+            (Some(result), None) => Ok(result),
+            (None, None) => Ok(Value::Null),
+        }
+    }
+}
+
+/// Calls the app if it is running; `None` means the caller should fall back
+/// to the state files.
+fn call_app(call: IpcCall) -> Option<Value> {
+    call_app_within(call, CALL_TIMEOUT)
+}
+
+fn call_app_within(call: IpcCall, timeout: Duration) -> Option<Value> {
+    let mut client = IpcClient::connect()?;
+    if let Err(e) = client.set_timeout(timeout) {
+        eprintln!("Warning: Failed to set IPC timeout: {}", e);
+    }
+    match client.call(call) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            eprintln!("Warning: Vocal app did not answer: {}", e);
+            None
+        }
+    }
+}
+
+/// Tells the app about a hook event, if it is running.
+pub fn report_hook_event(ctx: &HookContext) {
+    call_app(IpcCall::HookEvent(HookReport::from_hook(ctx)));
+}
+
+/// Asks the app to read out `last_message` and restart recording for the
+/// next cycle; returns whether it will.
+pub fn request_recording_restart(ctx: &HookContext, last_message: Option<String>) -> bool {
+    let report = StopReport { hook: HookReport::from_hook(ctx), last_message };
+    call_app(IpcCall::RestartRecording(report)).is_some()
+}
+
+/// Lets the app settle a tool call the safety rules want validated, which
+/// may mean waiting for the user to answer.
+pub fn request_decision(ctx: &HookContext, tool_use: &ToolUse, decision: &SafetyDecision) -> Option<DecisionReply> {
+    let request = DecisionRequest {
+        session_id: ctx.session_id.clone(),
+        cwd: ctx.cwd.clone(),
+        tool_name: tool_use.tool_name.clone(),
+        tool_input: tool_use.tool_input.clone(),
+        summary: tool_use.summary(),
+        reason: decision.reason.clone(),
+    };
+    let result = call_app_within(IpcCall::Decide(request), DECISION_TIMEOUT)?;
+    serde_json::from_value(result)
+        .map_err(|e| eprintln!("Warning: Invalid decision from Vocal app: {}", e))
+        .ok()
+}
+
+/// The app's view of hands-free mode, or the state files' when it isn't running.
+pub fn hands_free_status() -> HandsFreeStatus {
+    call_app(IpcCall::GetState)
+        .and_then(|result| serde_json::from_value(result).ok())
+        .unwrap_or_else(HandsFreeStatus::from_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    struct TestHandler;
+
+    impl IpcHandler for TestHandler {
+        fn handle(&self, call: IpcCall) -> Result<Value, String> {
+            match call {
+                IpcCall::GetState => Ok(json!(HandsFreeStatus { active: true, ..Default::default() })),
+                IpcCall::Decide(request) if request.tool_name == "Write" => Ok(json!(DecisionReply {
+                    decision: Some(PermissionDecision::Allow),
+                    reason: Some("voice approval".to_string()),
+                })),
+                IpcCall::Decide(_) => Ok(json!(DecisionReply::default())),
+                IpcCall::HookEvent(report) | IpcCall::RestartRecording(StopReport { hook: report, .. }) => {
+                    Err(format!("unexpected {} report", report.hook_event_name))
+                }
+            }
+        }
+    }
+
+    fn report() -> HookReport {
+        HookReport { hook_event_name: "Stop".to_string(), session_id: "a".to_string(), cwd: "/tmp".to_string() }
+    }
+
+    #[test]
+    fn test_request_wire_format() {
+        let report = StopReport { hook: report(), last_message: Some("Done.".to_string()) };
+        let request = IpcRequest { jsonrpc: "2.0".to_string(), id: 7, call: IpcCall::RestartRecording(report) };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "restartRecording",
+                "params": {"hookEventName": "Stop", "sessionId": "a", "cwd": "/tmp", "lastMessage": "Done."}
+            })
+        );
+
+        // Methods without params may omit them
+        let request: IpcRequest = serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"method":"getState"}"#).unwrap();
+        assert_eq!(request.call, IpcCall::GetState);
+    }
+
+    #[test]
+    fn test_client_server_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("vocal.sock");
+        let server = IpcServer::start(&path, TestHandler).unwrap();
+
+        let mut client = IpcClient::connect_to(server.path()).unwrap();
+        let status: HandsFreeStatus = serde_json::from_value(client.call(IpcCall::GetState).unwrap()).unwrap();
+        assert!(status.active);
+
+        let request = DecisionRequest {
+            session_id: "a".to_string(),
+            cwd: "/tmp".to_string(),
+            tool_name: "Write".to_string(),
+            tool_input: json!({"file_path": "/tmp/x", "content": ""}),
+            summary: "Write /tmp/x".to_string(),
+            reason: "writes outside the project".to_string(),
+        };
+        let reply: DecisionReply = serde_json::from_value(client.call(IpcCall::Decide(request)).unwrap()).unwrap();
+        assert_eq!(reply.decision, Some(PermissionDecision::Allow));
+
+        let error = client.call(IpcCall::HookEvent(report())).unwrap_err();
+        assert_eq!(error.to_string(), "app error -32603: unexpected Stop report");
+
+        // A second instance must not take over the socket
+        assert_eq!(IpcServer::start(&path, TestHandler).unwrap_err().kind(), io::ErrorKind::AddrInUse);
+
+        drop(server);
+        assert!(!path.exists());
+        assert!(IpcClient::connect_to(&path).is_err());
+    }
+
+    #[test]
+    fn test_malformed_requests() {
+        assert_eq!(respond("not json", &TestHandler).error.unwrap().code, PARSE_ERROR);
+
+        let response = respond(r#"{"jsonrpc":"2.0","id":3,"method":"selfDestruct"}"#, &TestHandler);
+        assert_eq!(response.id, Some(3));
+        let error = response.error.unwrap();
+        assert_eq!(error.code, INVALID_REQUEST);
+        assert!(error.message.contains("selfDestruct"));
+    }
+
+    #[test]
+    fn test_stale_socket_is_replaced() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("vocal.sock");
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+
+        let _server = IpcServer::start(&path, TestHandler).unwrap();
+        assert!(IpcClient::connect_to(&path).is_ok());
+    }
+}
@@ -1,39 +1,27 @@
+pub mod events;
 pub mod state;
+pub mod sessions;
+pub mod cycles;
 pub mod handlers;
 pub mod setup;
 pub mod safety;
+pub mod policy;
+pub mod shell;
+pub mod paths;
+pub mod output;
+pub mod ipc;
+pub mod transcript;
+pub mod audit;
+pub mod snapshots;
+pub mod verify;
+pub mod git;
 
-use serde_json::Value;
-use std::io::{self, Read};
-use std::error::Error;
-
-#[derive(Debug)]
-pub struct HookContext {
-    pub session_id: String,
-    pub transcript_path: String,
-    pub cwd: String,
-    pub hook_event_name: String,
-    pub data: Value,
-}
-
-impl HookContext {
-    pub fn from_stdin() -> Result<Self, Box<dyn Error>> {
-        let mut buffer = String::new();
-        io::stdin().read_to_string(&mut buffer)?;
-        
-        let data: Value = serde_json::from_str(&buffer)?;
-        
-        Ok(HookContext {
-            session_id: data["session_id"].as_str().unwrap_or("").to_string(),
-            transcript_path: data["transcript_path"].as_str().unwrap_or("").to_string(),
-            cwd: data["cwd"].as_str().unwrap_or("").to_string(),
-            hook_event_name: data["hook_event_name"].as_str().unwrap_or("").to_string(),
-            data,
-        })
-    }
-}
-
+pub use events::*;
 pub use handlers::*;
 pub use setup::*;
 pub use state::*;
-pub use safety::*;
\ No newline at end of file
+pub use sessions::*;
+pub use cycles::*;
+pub use safety::*;
+pub use policy::*;
+pub use output::*;
\ No newline at end of file
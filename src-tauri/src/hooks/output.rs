@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use crate::hooks::safety::{PermissionLevel, SafetyDecision};
+
+/// Claude Code only reads a hook's JSON output when the hook exits with 0.
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code that makes Claude Code block the action and show stderr to the model.
+pub const EXIT_BLOCKING: i32 = 2;
+
+/// The PreToolUse permission values Claude Code understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionDecision {
+    /// Skip the permission prompt and run the tool
+    Allow,
+    /// Refuse the tool call; the reason is shown to Claude
+    Deny,
+    /// Show the normal confirmation prompt; the reason is shown to the user
+    Ask,
+}
+
+impl From<PermissionLevel> for PermissionDecision {
+    fn from(level: PermissionLevel) -> Self {
+        match level {
+            PermissionLevel::Allow => PermissionDecision::Allow,
+            PermissionLevel::Validate => PermissionDecision::Ask,
+            PermissionLevel::Block => PermissionDecision::Deny,
+        }
+    }
+}
+
+impl From<PermissionDecision> for PermissionLevel {
+    fn from(decision: PermissionDecision) -> Self {
+        match decision {
+            PermissionDecision::Allow => PermissionLevel::Allow,
+            PermissionDecision::Ask => PermissionLevel::Validate,
+            PermissionDecision::Deny => PermissionLevel::Block,
+        }
+    }
+}
+
+/// The only value of the top-level `decision` field Claude Code acts on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockDecision {
+    Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "hookEventName")]
+pub enum HookSpecificOutput {
+    #[serde(rename_all = "camelCase")]
+    PreToolUse {
+        permission_decision: PermissionDecision,
+        permission_decision_reason: String,
+    },
+}
+
+/// JSON a hook prints on stdout. Fields left as `None` are omitted so
+/// Claude Code falls back to its defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutput {
+    /// `false` stops Claude entirely, after this hook, with `stop_reason`
+    #[serde(rename = "continue", skip_serializing_if = "Option::is_none")]
+    pub continue_: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_output: Option<bool>,
+    /// After a tool ran (PostToolUse), `block` sends `reason` to Claude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision: Option<BlockDecision>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hook_specific_output: Option<HookSpecificOutput>,
+}
+
+impl HookOutput {
+    pub fn pre_tool_use(decision: PermissionDecision, reason: impl Into<String>) -> Self {
+        HookOutput {
+            hook_specific_output: Some(HookSpecificOutput::PreToolUse {
+                permission_decision: decision,
+                permission_decision_reason: reason.into(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Tells Claude that something about the tool call it just made needs fixing.
+    pub fn block(reason: impl Into<String>) -> Self {
+        HookOutput {
+            decision: Some(BlockDecision::Block),
+            reason: Some(reason.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Stops Claude from doing anything further in this session.
+    pub fn stop(reason: impl Into<String>) -> Self {
+        HookOutput {
+            continue_: Some(false),
+            stop_reason: Some(reason.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// What a hook handler wants Claude Code to see.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookResponse {
+    /// No output; Claude Code proceeds as if the hook did not exist
+    Continue,
+    /// Structured JSON on stdout, exit code 0
+    Json(HookOutput),
+    /// Message on stderr with exit code 2, for when JSON is not an option
+    Blocking(String),
+}
+
+impl HookResponse {
+    /// The PreToolUse answer for a safety decision in hands-free mode.
+    pub fn for_safety_decision(tool_name: &str, decision: &SafetyDecision, suppress_output: bool) -> Self {
+        let reason = match decision.level {
+            PermissionLevel::Allow => format!("Hands-free mode: {} operation auto-approved by {}", tool_name, decision.reason),
+            PermissionLevel::Validate => format!("Hands-free mode: {} operation requires confirmation ({})", tool_name, decision.reason),
+            PermissionLevel::Block => format!("Hands-free mode: {} operation blocked by {}", tool_name, decision.reason),
+        };
+
+        let mut output = HookOutput::pre_tool_use(decision.level.into(), reason);
+        if suppress_output {
+            output.suppress_output = Some(true);
+        }
+        HookResponse::Json(output)
+    }
+
+    /// Writes the response and returns the exit code the hook process should use.
+    pub fn emit(&self) -> Result<i32, Box<dyn std::error::Error>> {
+        match self {
+            HookResponse::Continue => Ok(EXIT_SUCCESS),
+            HookResponse::Json(output) => {
+                let mut stdout = io::stdout().lock();
+                writeln!(stdout, "{}", serde_json::to_string(output)?)?;
+                Ok(EXIT_SUCCESS)
+            }
+            HookResponse::Blocking(message) => {
+                eprintln!("{}", message);
+                Ok(EXIT_BLOCKING)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(level: PermissionLevel) -> SafetyDecision {
+        SafetyDecision {
+            level,
+            reason: "built-in safety rules".to_string(),
+        }
+    }
+
+    fn json_of(response: &HookResponse) -> String {
+        match response {
+            HookResponse::Json(output) => serde_json::to_string(output).unwrap(),
+            other => panic!("expected JSON output, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_allow_golden() {
+        let response = HookResponse::for_safety_decision("Read", &decision(PermissionLevel::Allow), true);
+        assert_eq!(
+            json_of(&response),
+            r#"{"suppressOutput":true,"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"allow","permissionDecisionReason":"Hands-free mode: Read operation auto-approved by built-in safety rules"}}"#
+        );
+    }
+
+    #[test]
+    fn test_validate_golden() {
+        let response = HookResponse::for_safety_decision("Write", &decision(PermissionLevel::Validate), false);
+        assert_eq!(
+            json_of(&response),
+            r#"{"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"ask","permissionDecisionReason":"Hands-free mode: Write operation requires confirmation (built-in safety rules)"}}"#
+        );
+    }
+
+    #[test]
+    fn test_block_golden() {
+        let response = HookResponse::for_safety_decision("Bash", &decision(PermissionLevel::Block), false);
+        assert_eq!(
+            json_of(&response),
+            r#"{"hookSpecificOutput":{"hookEventName":"PreToolUse","permissionDecision":"deny","permissionDecisionReason":"Hands-free mode: Bash operation blocked by built-in safety rules"}}"#
+        );
+    }
+
+    #[test]
+    fn test_stop_golden() {
+        let output = HookOutput::stop("Vocal emergency stop is active");
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"continue":false,"stopReason":"Vocal emergency stop is active"}"#
+        );
+    }
+
+    #[test]
+    fn test_block_output_golden() {
+        let output = HookOutput::block("cargo check failed");
+        assert_eq!(
+            serde_json::to_string(&output).unwrap(),
+            r#"{"decision":"block","reason":"cargo check failed"}"#
+        );
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(HookResponse::Continue.emit().unwrap(), EXIT_SUCCESS);
+        assert_eq!(HookResponse::Blocking("no".to_string()).emit().unwrap(), EXIT_BLOCKING);
+    }
+}
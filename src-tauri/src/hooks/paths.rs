@@ -0,0 +1,156 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Where a Claude Code session may touch files without asking: its project
+/// root plus any extra workspaces listed in the user's policy file.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub cwd: PathBuf,
+    pub root: PathBuf,
+    pub allowed: Vec<PathBuf>,
+}
+
+impl Workspace {
+    pub fn new(cwd: &Path, allowed: &[PathBuf]) -> Self {
+        let cwd = canonicalize_lenient(&normalize(&expand_home(cwd)));
+        Workspace {
+            root: find_project_root(&cwd),
+            allowed: allowed
+                .iter()
+                .map(|path| canonicalize_lenient(&normalize(&expand_home(path))))
+                .collect(),
+            cwd,
+        }
+    }
+
+    /// The workspace of the current process, for callers without a hook `cwd`.
+    pub fn current() -> Self {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+        Self::new(&cwd, &[])
+    }
+
+    /// Resolves a path from a tool call the way the tool will see it:
+    /// `~` expanded, relative to the session's directory, `..` folded and
+    /// symlinks followed.
+    pub fn resolve(&self, raw: &str) -> PathBuf {
+        resolve_path(raw, &self.cwd)
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.root) || self.allowed.iter().any(|allowed| path.starts_with(allowed))
+    }
+}
+
+pub fn resolve_path(raw: &str, cwd: &Path) -> PathBuf {
+    let path = expand_home(Path::new(raw));
+    let absolute = if path.is_absolute() { path } else { cwd.join(path) };
+    canonicalize_lenient(&normalize(&absolute))
+}
+
+/// The nearest ancestor of `cwd` that looks like a project (a git checkout
+/// or a directory with its own `.vocal/`), or `cwd` itself.
+pub fn find_project_root(cwd: &Path) -> PathBuf {
+    let home = dirs::home_dir();
+    cwd.ancestors()
+        // Home's ~/.vocal is the user config, not a project marker
+        .filter(|dir| Some(*dir) != home.as_deref())
+        .find(|dir| dir.join(".git").exists() || dir.join(".vocal").is_dir())
+        .unwrap_or(cwd)
+        .to_path_buf()
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Folds `.` and `..` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Follows symlinks through the longest existing prefix of `path`, so files
+/// that are about to be created still resolve through linked directories.
+fn canonicalize_lenient(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
+
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing.iter().rev().fold(canonical, |path, part| path.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(Path::new("/work/app/./../../etc/passwd")), PathBuf::from("/etc/passwd"));
+        assert_eq!(normalize(Path::new("/../..")), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_resolve_relative_and_missing_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().canonicalize().unwrap().join("project");
+        fs::create_dir_all(project.join("src")).unwrap();
+
+        assert_eq!(resolve_path("./src/new.rs", &project), project.join("src/new.rs"));
+        assert_eq!(resolve_path("src/a/b/c.rs", &project), project.join("src/a/b/c.rs"));
+        assert_eq!(resolve_path("../outside.txt", &project), project.parent().unwrap().join("outside.txt"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_followed() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().canonicalize().unwrap();
+        let project = base.join("project");
+        let secrets = base.join("secrets");
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(&secrets).unwrap();
+        std::os::unix::fs::symlink(&secrets, project.join("link")).unwrap();
+
+        let workspace = Workspace::new(&project, &[]);
+        let resolved = workspace.resolve("link/id_rsa");
+        assert_eq!(resolved, secrets.join("id_rsa"));
+        assert!(!workspace.contains(&resolved));
+        assert!(Workspace::new(&project, &[secrets]).contains(&resolved));
+    }
+
+    #[test]
+    fn test_project_root_is_found_from_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().canonicalize().unwrap().join("project");
+        fs::create_dir_all(project.join(".git")).unwrap();
+        fs::create_dir_all(project.join("crates/app")).unwrap();
+
+        let workspace = Workspace::new(&project.join("crates/app"), &[]);
+        assert_eq!(workspace.root, project);
+        assert!(workspace.contains(&workspace.resolve("../../README.md")));
+        assert!(!workspace.contains(&workspace.resolve("../../../elsewhere.txt")));
+    }
+}
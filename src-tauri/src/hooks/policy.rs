@@ -0,0 +1,565 @@
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::hooks::paths::{find_project_root, resolve_path};
+use crate::hooks::safety::PermissionLevel;
+
+/// File names looked up in `~/.vocal/` and in a project's `.vocal/` directory.
+pub const POLICY_FILE_NAMES: [&str; 2] = ["policy.toml", "policy.json"];
+
+/// On-disk form of a policy file:
+///
+/// ```toml
+/// [[rules]]
+/// tool = "Bash"
+/// commands = ['^cargo (test|clippy)\b']
+/// action = "allow"
+///
+/// [[rules]]
+/// tool = "{Edit,Write,MultiEdit}"
+/// paths = ["src/**", "~/notes/**"]
+/// action = "allow"
+/// reason = "Project sources"
+/// ```
+///
+/// The user file may also list `workspaces = ["~/notes"]`: directories file
+/// tools may use outside the project without asking, and `[[validators]]`:
+/// checks run after hands-free edits.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PolicyFile {
+    pub workspaces: Vec<String>,
+    pub rules: Vec<PolicyRule>,
+    pub validators: Vec<ValidatorSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PolicyRule {
+    /// Glob over the tool name, e.g. `Read`, `mcp__*` or `{Edit,Write}`.
+    pub tool: String,
+    pub action: PermissionLevel,
+    /// Globs over the tool's file path. Relative patterns match paths
+    /// relative to the hook's working directory.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Regexes over the Bash command.
+    #[serde(default)]
+    pub commands: Vec<String>,
+    pub reason: Option<String>,
+}
+
+/// A command run after a hands-free Edit, Write or MultiEdit, in the
+/// session's directory with the changed file in `$VOCAL_FILE`:
+///
+/// ```toml
+/// [[validators]]
+/// name = "cargo check"
+/// command = "cargo check --quiet"
+/// paths = ["**/*.rs"]
+/// on_failure = "rollback"
+/// ```
+///
+/// A non-zero exit fails the edit.
+#[derive(Debug, Deserialize)]
+pub struct ValidatorSpec {
+    /// Shown to Claude and in logs; defaults to the command
+    pub name: Option<String>,
+    pub command: String,
+    /// Globs over the changed file, like a rule's `paths`; empty matches every file
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub on_failure: OnFailure,
+    pub timeout_secs: Option<u64>,
+}
+
+/// What happens to an edit a validator rejects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    /// Keep the edit and send the failure to Claude to fix
+    #[default]
+    Report,
+    /// Restore the file from its snapshot, then tell Claude why
+    Rollback,
+}
+
+#[derive(Debug, Clone)]
+pub struct Validator {
+    pub name: String,
+    pub command: String,
+    paths: Vec<GlobMatcher>,
+    pub on_failure: OnFailure,
+    pub timeout: Duration,
+}
+
+impl Validator {
+    pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+    fn compile(index: usize, spec: ValidatorSpec) -> Result<Self, String> {
+        let paths = spec
+            .paths
+            .iter()
+            .map(|pattern| {
+                path_glob(pattern)
+                    .map_err(|e| format!("validator {}: invalid path pattern '{}': {}", index + 1, pattern, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Validator {
+            name: spec.name.unwrap_or_else(|| spec.command.clone()),
+            command: spec.command,
+            paths,
+            on_failure: spec.on_failure,
+            timeout: Duration::from_secs(spec.timeout_secs.unwrap_or(Self::DEFAULT_TIMEOUT_SECS)),
+        })
+    }
+
+    pub fn applies_to(&self, file_path: &str, cwd: &Path) -> bool {
+        let candidates = path_candidates(file_path, cwd);
+        self.paths.is_empty() || self.paths.iter().any(|glob| candidates.iter().any(|path| glob.is_match(path)))
+    }
+}
+
+struct CompiledRule {
+    index: usize,
+    tool: GlobMatcher,
+    action: PermissionLevel,
+    paths: Vec<GlobMatcher>,
+    commands: Vec<Regex>,
+    reason: Option<String>,
+}
+
+impl CompiledRule {
+    fn compile(index: usize, rule: PolicyRule) -> Result<Self, String> {
+        let tool = Glob::new(&rule.tool)
+            .map_err(|e| format!("rule {}: invalid tool pattern '{}': {}", index + 1, rule.tool, e))?
+            .compile_matcher();
+
+        let paths = rule
+            .paths
+            .iter()
+            .map(|pattern| {
+                path_glob(pattern).map_err(|e| format!("rule {}: invalid path pattern '{}': {}", index + 1, pattern, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let commands = rule
+            .commands
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| format!("rule {}: invalid command pattern '{}': {}", index + 1, pattern, e))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(CompiledRule {
+            index,
+            tool,
+            action: rule.action,
+            paths,
+            commands,
+            reason: rule.reason,
+        })
+    }
+
+    fn matches(&self, tool_name: &str, tool_input: &Value, cwd: &Path) -> bool {
+        if !self.tool.is_match(tool_name) {
+            return false;
+        }
+
+        if !self.paths.is_empty() {
+            let Some(file_path) = tool_file_path(tool_input) else {
+                return false;
+            };
+            let candidates = path_candidates(file_path, cwd);
+            if !self.paths.iter().any(|glob| candidates.iter().any(|path| glob.is_match(path))) {
+                return false;
+            }
+        }
+
+        if !self.commands.is_empty() {
+            let Some(command) = tool_input.get("command").and_then(|v| v.as_str()) else {
+                return false;
+            };
+            if !self.commands.iter().any(|regex| regex.is_match(command)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Rules from one policy file.
+pub struct PolicyLayer {
+    pub source: PathBuf,
+    /// Comes with the project, so it may only tighten the rules
+    pub project: bool,
+    pub workspaces: Vec<PathBuf>,
+    pub validators: Vec<Validator>,
+    rules: Vec<CompiledRule>,
+}
+
+impl PolicyLayer {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)?;
+        let file: PolicyFile = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content).map_err(|e| format!("Invalid policy in {}: {}", path.display(), e))?
+        } else {
+            toml::from_str(&content).map_err(|e| format!("Invalid policy in {}: {}", path.display(), e))?
+        };
+        Self::from_file(path.to_path_buf(), file)
+            .map_err(|e| format!("Invalid policy in {}: {}", path.display(), e).into())
+    }
+
+    pub fn from_file(source: PathBuf, file: PolicyFile) -> Result<Self, String> {
+        let rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| CompiledRule::compile(index, rule))
+            .collect::<Result<_, _>>()?;
+        let validators = file
+            .validators
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| Validator::compile(index, spec))
+            .collect::<Result<_, _>>()?;
+        let workspaces = file.workspaces.iter().map(|path| PathBuf::from(expand_home(path))).collect();
+        Ok(PolicyLayer { source, project: false, workspaces, validators, rules })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// Verdict reached by a policy rule, with where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyDecision {
+    pub level: PermissionLevel,
+    pub source: PathBuf,
+    /// 1-based position of the rule in its file.
+    pub rule: usize,
+    pub reason: Option<String>,
+    /// From a project file, which can't loosen the built-in rules
+    pub project: bool,
+}
+
+impl PolicyDecision {
+    pub fn describe(&self) -> String {
+        let origin = format!("policy rule {} in {}", self.rule, self.source.display());
+        match &self.reason {
+            Some(reason) => format!("{} ({})", reason, origin),
+            None => origin,
+        }
+    }
+}
+
+/// Policy layers in precedence order, highest first: the project's
+/// `.vocal/policy.toml`, then `~/.vocal/policy.toml`.
+///
+/// A matching `block` rule in any layer wins. Otherwise the first matching
+/// rule of the highest layer decides. When nothing matches the built-in
+/// safety rules apply. A cloned repository must not unlock its own tool
+/// calls, so `allow` rules in a project file are ignored and its other
+/// rules can only make the built-in verdict stricter.
+#[derive(Default)]
+pub struct Policy {
+    pub layers: Vec<PolicyLayer>,
+    /// Extra workspaces from the user file. Project files' `workspaces` are
+    /// ignored along with their `allow` rules.
+    pub workspaces: Vec<PathBuf>,
+    /// Validators from the user file. They run commands no safety rule has
+    /// judged, so project files' `validators` are ignored too.
+    pub validators: Vec<Validator>,
+}
+
+impl Policy {
+    pub fn new(layers: Vec<PolicyLayer>) -> Self {
+        Policy { layers, workspaces: Vec::new(), validators: Vec::new() }
+    }
+
+    /// Loads the project policy found from `cwd` upwards and the user policy.
+    pub fn load(cwd: &Path) -> Result<Self, Box<dyn Error>> {
+        let user_dir = user_policy_dir();
+        let mut layers = Vec::new();
+
+        if let Some(path) = find_project_policy(cwd, &user_dir) {
+            let mut layer = PolicyLayer::load(&path)?;
+            layer.project = true;
+            layers.push(layer);
+        }
+        let mut workspaces = Vec::new();
+        let mut validators = Vec::new();
+        if let Some(path) = find_policy_file(&user_dir) {
+            let layer = PolicyLayer::load(&path)?;
+            workspaces = layer.workspaces.clone();
+            validators = layer.validators.clone();
+            layers.push(layer);
+        }
+
+        Ok(Policy { layers, workspaces, validators })
+    }
+
+    /// Validators for a change to `file_path`, in file order.
+    pub fn validators_for<'a>(&'a self, file_path: &'a str, cwd: &'a Path) -> impl Iterator<Item = &'a Validator> {
+        self.validators.iter().filter(move |validator| validator.applies_to(file_path, cwd))
+    }
+
+    pub fn evaluate(&self, tool_name: &str, tool_input: &Value, cwd: &Path) -> Option<PolicyDecision> {
+        let mut first_match = None;
+
+        for layer in &self.layers {
+            let rules = layer.rules.iter().filter(|rule| !(layer.project && rule.action == PermissionLevel::Allow));
+            for rule in rules.filter(|rule| rule.matches(tool_name, tool_input, cwd)) {
+                let decision = PolicyDecision {
+                    level: rule.action,
+                    source: layer.source.clone(),
+                    rule: rule.index + 1,
+                    reason: rule.reason.clone(),
+                    project: layer.project,
+                };
+                if decision.level == PermissionLevel::Block {
+                    return Some(decision);
+                }
+                if first_match.is_none() {
+                    first_match = Some(decision);
+                }
+            }
+        }
+
+        first_match
+    }
+}
+
+pub fn user_policy_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal")
+}
+
+fn find_policy_file(dir: &Path) -> Option<PathBuf> {
+    POLICY_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Walks up from `cwd` to the nearest `.vocal/` directory holding a policy,
+/// no further than the project root, and skipping the user-level directory
+/// so it is not loaded twice. Directories anyone may write to, like `/tmp`,
+/// can't hold the policy of the sessions below them.
+fn find_project_policy(cwd: &Path, user_dir: &Path) -> Option<PathBuf> {
+    let root = find_project_root(cwd);
+    let dirs = cwd.ancestors().take_while(|dir| dir.starts_with(&root));
+    dirs.filter(|dir| !is_world_writable(dir))
+        .map(|dir| dir.join(".vocal"))
+        .filter(|dir| dir != user_dir)
+        .find_map(|dir| find_policy_file(&dir))
+}
+
+#[cfg(unix)]
+fn is_world_writable(dir: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(dir).is_ok_and(|metadata| metadata.permissions().mode() & 0o002 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_world_writable(_dir: &Path) -> bool {
+    false
+}
+
+/// The path a file tool operates on, if any.
+pub fn tool_file_path(tool_input: &Value) -> Option<&str> {
+    ["file_path", "notebook_path", "path"]
+        .iter()
+        .find_map(|key| tool_input.get(*key).and_then(|v| v.as_str()))
+}
+
+fn path_glob(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    GlobBuilder::new(&expand_home(pattern))
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+}
+
+fn expand_home(pattern: &str) -> String {
+    match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest).to_string_lossy().to_string(),
+        _ => pattern.to_string(),
+    }
+}
+
+// Globs may be written relative to the project or as absolute paths, so try
+// both forms of the resolved path
+fn path_candidates(file_path: &str, cwd: &Path) -> Vec<PathBuf> {
+    let resolved = resolve_path(file_path, cwd);
+    let mut candidates = vec![resolved.clone()];
+    if let Ok(relative) = resolved.strip_prefix(resolve_path(".", cwd)) {
+        candidates.push(relative.to_path_buf());
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn layer(source: &str, policy: &str) -> PolicyLayer {
+        PolicyLayer::from_file(PathBuf::from(source), toml::from_str(policy).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_rules_match_tool_paths_and_commands() {
+        let policy = Policy::new(vec![layer(
+            "policy.toml",
+            r#"
+            [[rules]]
+            tool = "Bash"
+            commands = ['^cargo (test|clippy)\b']
+            action = "allow"
+
+            [[rules]]
+            tool = "{Edit,Write}"
+            paths = ["src/**"]
+            action = "allow"
+            reason = "Project sources"
+
+            [[rules]]
+            tool = "mcp__*"
+            action = "block"
+            "#,
+        )]);
+        let cwd = Path::new("/work/project");
+
+        let decision = policy.evaluate("Bash", &json!({"command": "cargo test --workspace"}), cwd).unwrap();
+        assert_eq!(decision.level, PermissionLevel::Allow);
+        assert_eq!(decision.rule, 1);
+        assert!(policy.evaluate("Bash", &json!({"command": "cargo publish"}), cwd).is_none());
+
+        let edit = policy.evaluate("Edit", &json!({"file_path": "/work/project/src/lib.rs"}), cwd).unwrap();
+        assert_eq!(edit.describe(), "Project sources (policy rule 2 in policy.toml)");
+        assert!(policy.evaluate("Write", &json!({"file_path": "./src/nested/mod.rs"}), cwd).is_some());
+        assert!(policy.evaluate("Edit", &json!({"file_path": "/work/other/src/lib.rs"}), cwd).is_none());
+        assert!(policy.evaluate("Edit", &json!({}), cwd).is_none());
+
+        assert_eq!(
+            policy.evaluate("mcp__github__create_issue", &json!({}), cwd).unwrap().level,
+            PermissionLevel::Block
+        );
+    }
+
+    #[test]
+    fn test_project_layer_only_tightens_and_block_always_wins() {
+        let mut project = layer(
+            "project",
+            "[[rules]]\ntool = \"Bash\"\ncommands = ['^ls']\naction = \"allow\"\n\n[[rules]]\ntool = \"Bash\"\ncommands = ['^git']\naction = \"validate\"\n",
+        );
+        project.project = true;
+        let user = layer(
+            "user",
+            "[[rules]]\ntool = \"Bash\"\naction = \"allow\"\n\n[[rules]]\ntool = \"Bash\"\ncommands = ['rm\\s+-rf']\naction = \"block\"\n",
+        );
+        let policy = Policy::new(vec![project, user]);
+        let cwd = Path::new("/work");
+
+        let ls = policy.evaluate("Bash", &json!({"command": "ls"}), cwd).unwrap();
+        assert_eq!((ls.level, ls.source), (PermissionLevel::Allow, PathBuf::from("user")));
+
+        let git = policy.evaluate("Bash", &json!({"command": "git push"}), cwd).unwrap();
+        assert_eq!((git.level, git.source, git.project), (PermissionLevel::Validate, PathBuf::from("project"), true));
+
+        let rm = policy.evaluate("Bash", &json!({"command": "rm -rf build"}), cwd).unwrap();
+        assert_eq!((rm.level, rm.source), (PermissionLevel::Block, PathBuf::from("user")));
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        let file: PolicyFile = toml::from_str("[[rules]]\ntool = \"Bash\"\ncommands = ['(']\naction = \"allow\"\n").unwrap();
+        let error = PolicyLayer::from_file(PathBuf::from("policy.toml"), file).err().unwrap();
+        assert!(error.contains("rule 1"));
+
+        assert!(toml::from_str::<PolicyFile>("[[rules]]\ntool = \"Bash\"\naction = \"maybe\"\n").is_err());
+    }
+
+    #[test]
+    fn test_validators_match_changed_files() {
+        let layer = layer(
+            "policy.toml",
+            r#"
+            [[validators]]
+            command = "cargo check --quiet"
+            paths = ["**/*.rs"]
+            on_failure = "rollback"
+
+            [[validators]]
+            name = "whitespace"
+            command = "! grep -n ' $' \"$VOCAL_FILE\""
+            timeout_secs = 5
+            "#,
+        );
+        let policy = Policy { validators: layer.validators.clone(), ..Policy::new(vec![layer]) };
+        let cwd = Path::new("/work/project");
+
+        let names: Vec<&str> = policy.validators_for("src/lib.rs", cwd).map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["cargo check --quiet", "whitespace"]);
+        assert_eq!(policy.validators[0].on_failure, OnFailure::Rollback);
+        assert_eq!(policy.validators[1].on_failure, OnFailure::Report);
+        assert_eq!(policy.validators[1].timeout, Duration::from_secs(5));
+        assert_eq!(policy.validators_for("/work/project/README.md", cwd).count(), 1);
+    }
+
+    #[test]
+    fn test_project_policy_found_from_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".vocal")).unwrap();
+        fs::create_dir_all(root.join("crates/app")).unwrap();
+        fs::write(
+            root.join(".vocal/policy.json"),
+            r#"{"rules": [{"tool": "Read", "action": "block"}]}"#,
+        )
+        .unwrap();
+
+        let path = find_project_policy(&root.join("crates/app"), Path::new("/nonexistent")).unwrap();
+        assert_eq!(path, root.join(".vocal/policy.json"));
+        assert_eq!(PolicyLayer::load(&path).unwrap().len(), 1);
+
+        assert!(find_project_policy(&root.join("crates/app"), &root.join(".vocal")).is_none());
+    }
+
+    #[test]
+    fn test_project_policy_is_not_looked_up_above_project_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        fs::create_dir_all(base.join(".vocal")).unwrap();
+        fs::write(base.join(".vocal/policy.toml"), "[[rules]]\ntool = \"Read\"\naction = \"block\"\n").unwrap();
+        fs::create_dir_all(base.join("checkout/.git")).unwrap();
+        fs::create_dir_all(base.join("checkout/src")).unwrap();
+        assert_eq!(find_project_policy(&base.join("checkout/src"), Path::new("/nonexistent")), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let shared = base.join("shared");
+            fs::create_dir_all(shared.join(".vocal")).unwrap();
+            fs::write(shared.join(".vocal/policy.toml"), "").unwrap();
+            fs::set_permissions(&shared, fs::Permissions::from_mode(0o1777)).unwrap();
+            assert_eq!(find_project_policy(&shared, Path::new("/nonexistent")), None);
+        }
+    }
+}
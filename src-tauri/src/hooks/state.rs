@@ -1,42 +1,98 @@
-use std::path::PathBuf;
-use std::fs;
+use std::path::{Path, PathBuf};
+use std::fs::{self, OpenOptions};
+use fs2::FileExt;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use chrono::{DateTime, Utc};
 use crate::hooks::HookContext;
+use crate::hooks::cycles::CycleTracker;
+use crate::hooks::sessions::{SessionInfo, SessionRegistry, SessionStatus};
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct SessionInfo {
-    pub session_id: String,
-    pub terminal_pid: String,
-    pub term_session: String,
-    pub iterm_session: String,
-    pub tmux: String,
-    pub cwd: String,
-    pub timestamp: i64,
+/// Loads the JSON state file at `path` (or a default when it is missing or
+/// corrupt), lets `f` modify it and writes it back if it changed. Hooks of
+/// concurrent Claude Code sessions share these files, so the whole
+/// read-modify-write holds an exclusive lock and the file is replaced
+/// atomically.
+pub fn update_locked_json<S, T>(path: &Path, f: impl FnOnce(&mut S) -> T) -> Result<T, Box<dyn std::error::Error>>
+where
+    S: Default + Serialize + DeserializeOwned,
+{
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // The lock lives in its own file because the state file is replaced on every write
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("json.lock"))?;
+    lock.lock_exclusive()?;
+
+    let original = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    // Corrupt state only costs what it tracked, never the hook
+    let mut value: S = serde_json::from_str(&original).unwrap_or_default();
+
+    let result = f(&mut value);
+
+    let updated = serde_json::to_string_pretty(&value)?;
+    if updated != original {
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, updated)?;
+        fs::rename(&tmp_path, path)?;
+    }
+
+    lock.unlock()?;
+    Ok(result)
 }
 
-pub fn hands_free_flag_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join(".vocal-hands-free-active")
+/// The app's `~/.vocal/config.toml`, of which the hooks read single tables.
+pub fn config_toml_path() -> PathBuf {
+    home_dir().join(".vocal/config.toml")
 }
 
-pub fn session_registry_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join(".vocal-session-registry.json")
+/// Reads only the `key` table of the config file so the hook binary doesn't
+/// depend on the rest of the app configuration. A missing file or table
+/// gives the defaults.
+pub fn load_config_table<T: Default + DeserializeOwned>(path: &Path, key: &str) -> Result<T, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let table: toml::Table = toml::from_str(&fs::read_to_string(path)?)?;
+    match table.get(key) {
+        Some(section) => Ok(section.clone().try_into()?),
+        None => Ok(T::default()),
+    }
+}
+
+/// Where the state files live. Tests point it at a temporary directory, for
+/// their own thread only.
+fn home_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(home) = tests::HOME.with(|home| home.borrow().clone()) {
+        return home;
+    }
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"))
+}
+
+pub fn hands_free_flag_path() -> PathBuf {
+    home_dir().join(".vocal-hands-free-active")
 }
 
 pub fn cycle_trigger_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join(".vocal-cycle-trigger")
+    home_dir().join(".vocal-cycle-trigger")
 }
 
 pub fn emergency_stop_path() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("/tmp"))
-        .join(".vocal-emergency-stop")
+    home_dir().join(".vocal-emergency-stop")
+}
+
+/// Why hands-free mode was last switched off automatically.
+pub fn hands_free_stop_reason_path() -> PathBuf {
+    home_dir().join(".vocal-hands-free-stopped")
 }
 
 pub fn is_hands_free_active() -> bool {
@@ -47,26 +103,21 @@ pub fn is_emergency_stop_active() -> bool {
     emergency_stop_path().exists()
 }
 
-pub fn save_session_info(ctx: &HookContext) -> Result<(), Box<dyn std::error::Error>> {
-    let session_info = SessionInfo {
-        session_id: ctx.session_id.clone(),
-        terminal_pid: std::env::var("PPID").unwrap_or_default(),
-        term_session: std::env::var("TERM_SESSION_ID").unwrap_or_default(),
-        iterm_session: std::env::var("ITERM_SESSION_ID").unwrap_or_default(),
-        tmux: std::env::var("TMUX").unwrap_or_default(),
-        cwd: ctx.cwd.clone(),
-        timestamp: Utc::now().timestamp(),
-    };
-    
-    let json = serde_json::to_string_pretty(&session_info)?;
-    fs::write(session_registry_path(), json)?;
-    Ok(())
+/// Registers or refreshes the hook's session in the session registry.
+pub fn save_session_info(ctx: &HookContext, status: SessionStatus) -> Result<(), Box<dyn std::error::Error>> {
+    SessionRegistry::open().register(SessionInfo::from_hook(ctx, status))
 }
 
+/// The default target session: the one that most recently stopped.
 pub fn load_session_info() -> Result<SessionInfo, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(session_registry_path())?;
-    let session_info: SessionInfo = serde_json::from_str(&content)?;
-    Ok(session_info)
+    SessionRegistry::open()
+        .last_stopped()?
+        .ok_or_else(|| "No Claude Code session is registered".into())
+}
+
+/// Removes a session from the registry; returns whether it was registered.
+pub fn clear_session_info(session_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    SessionRegistry::open().remove(session_id)
 }
 
 pub fn trigger_recording_restart() -> Result<(), std::io::Error> {
@@ -85,6 +136,15 @@ pub fn clear_cycle_trigger() -> Result<(), std::io::Error> {
 pub fn activate_hands_free_mode() -> Result<(), std::io::Error> {
     let timestamp = Utc::now().timestamp().to_string();
     fs::write(hands_free_flag_path(), timestamp)?;
+    // Switching it on again is the user's all-clear after an emergency stop
+    clear_emergency_stop()?;
+    if hands_free_stop_reason_path().exists() {
+        fs::remove_file(hands_free_stop_reason_path())?;
+    }
+    // Every activation starts with fresh cycle limits
+    if let Err(e) = CycleTracker::open().reset() {
+        eprintln!("Warning: Failed to reset hands-free cycles: {}", e);
+    }
     Ok(())
 }
 
@@ -96,6 +156,19 @@ pub fn deactivate_hands_free_mode() -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Switches hands-free mode off and records why, for the app to show.
+pub fn stop_hands_free_mode(reason: &str) -> Result<(), std::io::Error> {
+    fs::write(hands_free_stop_reason_path(), reason)?;
+    deactivate_hands_free_mode()
+}
+
+pub fn hands_free_stop_reason() -> Option<String> {
+    fs::read_to_string(hands_free_stop_reason_path())
+        .ok()
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty())
+}
+
 pub fn trigger_emergency_stop() -> Result<(), std::io::Error> {
     let timestamp = Utc::now().timestamp().to_string();
     fs::write(emergency_stop_path(), timestamp)?;
@@ -108,4 +181,31 @@ pub fn clear_emergency_stop() -> Result<(), std::io::Error> {
         fs::remove_file(emergency_stop_path())?;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::hooks::ipc::HandsFreeStatus;
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    thread_local! {
+        pub(crate) static HOME: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+    }
+
+    #[test]
+    fn test_emergency_stop_halts_until_cleared() {
+        let temp_dir = TempDir::new().unwrap();
+        HOME.with(|home| *home.borrow_mut() = Some(temp_dir.path().to_path_buf()));
+        fs::write(hands_free_flag_path(), "1").unwrap();
+
+        trigger_emergency_stop().unwrap();
+        let status = HandsFreeStatus::from_files();
+        assert!(!status.active && status.emergency_stop);
+        assert!(status.halts_claude() && !status.is_running());
+
+        clear_emergency_stop().unwrap();
+        assert!(!HandsFreeStatus::from_files().halts_claude());
+    }
+}
@@ -0,0 +1,457 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Tools whose successful calls change the file named in their input.
+const FILE_CHANGING_TOOLS: &[(&str, &str)] = &[
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("Write", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
+/// One line of a Claude Code session transcript.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TranscriptEntry {
+    User(MessageEntry),
+    Assistant(MessageEntry),
+    Summary(SummaryEntry),
+    /// System messages and entry types this version doesn't know
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageEntry {
+    #[serde(default)]
+    pub uuid: String,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// Written by a subagent rather than the main conversation
+    #[serde(default)]
+    pub is_sidechain: bool,
+    /// Injected by Claude Code, e.g. command output, not typed by the user
+    #[serde(default)]
+    pub is_meta: bool,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SummaryEntry {
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Message {
+    /// Shared by the entries of one assistant message, which is written one
+    /// content block per line
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default, deserialize_with = "content_blocks")]
+    pub content: Vec<ContentBlock>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default)]
+        content: Value,
+        #[serde(default)]
+        is_error: bool,
+    },
+    Thinking {
+        #[serde(default)]
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// A prompt is stored as a plain string, everything else as a list of blocks.
+fn content_blocks<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<ContentBlock>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Content {
+        Text(String),
+        Blocks(Vec<ContentBlock>),
+    }
+
+    Ok(match Content::deserialize(deserializer)? {
+        Content::Text(text) => vec![ContentBlock::Text { text }],
+        Content::Blocks(blocks) => blocks,
+    })
+}
+
+/// Token usage as the API reports it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all(serialize = "camelCase"))]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+impl Usage {
+    /// Input and output tokens, the way the cycle limits count them.
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+}
+
+impl MessageEntry {
+    fn texts(&self) -> impl Iterator<Item = &str> {
+        self.message.content.iter().filter_map(|block| match block {
+            ContentBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether the user typed this, as opposed to a tool result Claude Code
+    /// sends back under the user role.
+    fn is_prompt(&self) -> bool {
+        !self.is_sidechain && !self.is_meta && self.texts().next().is_some()
+    }
+}
+
+/// A parsed session transcript, `HookContext::transcript_path`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Parses JSONL content; lines that aren't transcript entries, such as
+    /// one Claude Code is still writing, are skipped.
+    pub fn parse(content: &str) -> Self {
+        let entries = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        Transcript { entries }
+    }
+
+    /// Everything from the last prompt the user typed on.
+    pub fn last_turn(&self) -> Turn<'_> {
+        let start = self
+            .entries
+            .iter()
+            .rposition(|entry| matches!(entry, TranscriptEntry::User(user) if user.is_prompt()))
+            .unwrap_or(0);
+        Turn { entries: &self.entries[start..] }
+    }
+
+    /// Token usage of the whole session.
+    pub fn usage(&self) -> Usage {
+        Turn { entries: &self.entries }.usage()
+    }
+}
+
+/// A prompt and everything Claude did for it.
+#[derive(Debug, Clone, Copy)]
+pub struct Turn<'a> {
+    entries: &'a [TranscriptEntry],
+}
+
+/// A tool call and, once it ran, its result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall<'a> {
+    pub id: &'a str,
+    pub name: &'a str,
+    pub input: &'a Value,
+    pub result: Option<ToolResult<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResult<'a> {
+    pub content: &'a Value,
+    pub is_error: bool,
+}
+
+impl<'a> Turn<'a> {
+    /// Entries of the main conversation; subagents' work is summed up in
+    /// the Task tool's result.
+    fn messages(&self) -> impl Iterator<Item = (&'a TranscriptEntry, &'a MessageEntry)> {
+        self.entries.iter().filter_map(|entry| match entry {
+            TranscriptEntry::User(message) | TranscriptEntry::Assistant(message) if !message.is_sidechain => {
+                Some((entry, message))
+            }
+            _ => None,
+        })
+    }
+
+    pub fn prompt(&self) -> Option<String> {
+        match self.messages().next()? {
+            (TranscriptEntry::User(user), _) if user.is_prompt() => Some(user.texts().collect::<Vec<_>>().join("\n")),
+            _ => None,
+        }
+    }
+
+    /// Claude's final response: the text of the assistant entries after the
+    /// last user entry (a prompt or a tool result). `None` when the turn
+    /// ended without text.
+    pub fn last_assistant_message(&self) -> Option<String> {
+        let mut texts: Vec<&str> = Vec::new();
+        for (entry, message) in self.messages() {
+            match entry {
+                TranscriptEntry::User(_) => texts.clear(),
+                _ => texts.extend(message.texts()),
+            }
+        }
+        let message = texts.join("\n\n");
+        Some(message).filter(|message| !message.trim().is_empty())
+    }
+
+    /// Tool calls in the order Claude made them, with their results.
+    pub fn tool_calls(&self) -> Vec<ToolCall<'a>> {
+        let mut calls: Vec<ToolCall<'a>> = Vec::new();
+        for (_, message) in self.messages() {
+            for block in &message.message.content {
+                match block {
+                    ContentBlock::ToolUse { id, name, input } => calls.push(ToolCall { id, name, input, result: None }),
+                    ContentBlock::ToolResult { tool_use_id, content, is_error } => {
+                        if let Some(call) = calls.iter_mut().find(|call| call.id == tool_use_id) {
+                            call.result = Some(ToolResult { content, is_error: *is_error });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        calls
+    }
+
+    /// How often each tool was called.
+    pub fn tools_used(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for call in self.tool_calls() {
+            *counts.entry(call.name.to_string()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Files edited or written by tool calls that succeeded, in order of the
+    /// first change.
+    pub fn files_changed(&self) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for call in self.tool_calls() {
+            let Some((_, key)) = FILE_CHANGING_TOOLS.iter().find(|(tool, _)| *tool == call.name) else {
+                continue;
+            };
+            if call.result.as_ref().is_some_and(|result| result.is_error) {
+                continue;
+            }
+            if let Some(path) = call.input[*key].as_str() {
+                if !files.iter().any(|file| file == path) {
+                    files.push(path.to_string());
+                }
+            }
+        }
+        files
+    }
+
+    /// Token usage, counting each assistant message once even though its
+    /// usage is repeated on every line it spans.
+    pub fn usage(&self) -> Usage {
+        let mut by_message: HashMap<&str, Usage> = HashMap::new();
+        let mut total = Usage::default();
+        for (entry, message) in self.messages() {
+            let (TranscriptEntry::Assistant(_), Some(usage)) = (entry, &message.message.usage) else {
+                continue;
+            };
+            match &message.message.id {
+                Some(id) => {
+                    by_message.insert(id, *usage);
+                }
+                None => total.add(usage),
+            }
+        }
+        by_message.values().for_each(|usage| total.add(usage));
+        total
+    }
+
+    pub fn summary(&self) -> TurnSummary {
+        let tool_calls = self.tool_calls();
+        TurnSummary {
+            prompt: self.prompt(),
+            response: self.last_assistant_message(),
+            tools_used: self.tools_used(),
+            failed_tool_calls: tool_calls
+                .iter()
+                .filter(|call| call.result.as_ref().is_some_and(|result| result.is_error))
+                .count(),
+            files_changed: self.files_changed(),
+            usage: self.usage(),
+        }
+    }
+}
+
+/// What happened in a turn, for the Stop hook, the app and `vocal transcript summarize`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnSummary {
+    pub prompt: Option<String>,
+    pub response: Option<String>,
+    pub tools_used: BTreeMap<String, usize>,
+    pub failed_tool_calls: usize,
+    pub files_changed: Vec<String>,
+    pub usage: Usage,
+}
+
+impl TurnSummary {
+    pub fn tool_call_count(&self) -> usize {
+        self.tools_used.values().sum()
+    }
+}
+
+impl fmt::Display for TurnSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Prompt: {}", self.prompt.as_deref().unwrap_or("(none)"))?;
+
+        let tools: Vec<String> = self.tools_used.iter().map(|(name, count)| format!("{} ×{}", name, count)).collect();
+        write!(f, "Tools: {}", if tools.is_empty() { "none".to_string() } else { tools.join(", ") })?;
+        if self.failed_tool_calls > 0 {
+            write!(f, " ({} failed)", self.failed_tool_calls)?;
+        }
+        writeln!(f)?;
+
+        if self.files_changed.is_empty() {
+            writeln!(f, "Files changed: none")?;
+        } else {
+            writeln!(f, "Files changed:")?;
+            for file in &self.files_changed {
+                writeln!(f, "  {}", file)?;
+            }
+        }
+        writeln!(f, "Tokens: {} in, {} out", self.usage.input_tokens, self.usage.output_tokens)?;
+        write!(f, "Response: {}", self.response.as_deref().unwrap_or("(none)"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transcript(entries: &[Value]) -> Transcript {
+        let lines: Vec<String> = entries.iter().map(Value::to_string).collect();
+        Transcript::parse(&(lines.join("\n") + "\nnot json\n{\"type\": \"assistant\""))
+    }
+
+    fn sample() -> Transcript {
+        let usage = json!({"input_tokens": 100, "output_tokens": 20, "cache_read_input_tokens": 5000});
+        transcript(&[
+            json!({"type": "user", "message": {"role": "user", "content": "rename the config"}}),
+            json!({"type": "assistant", "message": {"id": "m0", "content": [{"type": "text", "text": "Old turn."}]}}),
+            json!({"type": "summary", "summary": "Renaming config"}),
+            json!({"type": "user", "message": {"role": "user", "content": "fix the tests"}}),
+            json!({"type": "assistant", "message": {"id": "m1", "usage": usage, "content": [{"type": "text", "text": "Looking."}]}}),
+            json!({"type": "assistant", "message": {"id": "m1", "usage": usage, "content": [
+                {"type": "tool_use", "id": "t1", "name": "Edit", "input": {"file_path": "/p/src/lib.rs", "old_string": "a", "new_string": "b"}}
+            ]}}),
+            json!({"type": "user", "message": {"content": [{"type": "tool_result", "tool_use_id": "t1", "content": "ok"}]}}),
+            json!({"type": "assistant", "isSidechain": true, "message": {"content": [{"type": "text", "text": "Subagent."}]}}),
+            json!({"type": "assistant", "message": {"id": "m2", "usage": {"input_tokens": 300, "output_tokens": 40}, "content": [
+                {"type": "thinking", "thinking": "hmm"},
+                {"type": "tool_use", "id": "t2", "name": "Write", "input": {"file_path": "/etc/hosts", "content": ""}},
+                {"type": "tool_use", "id": "t3", "name": "Bash", "input": {"command": "cargo test"}}
+            ]}}),
+            json!({"type": "user", "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "t2", "content": "denied", "is_error": true},
+                {"type": "tool_result", "tool_use_id": "t3", "content": [{"type": "text", "text": "ok"}]}
+            ]}}),
+            json!({"type": "assistant", "message": {"id": "m3", "content": [{"type": "text", "text": "All tests pass."}]}}),
+            json!({"type": "assistant", "message": {"id": "m3", "content": [{"type": "text", "text": "Anything else?"}]}}),
+        ])
+    }
+
+    #[test]
+    fn test_entries_are_typed() {
+        let transcript = sample();
+        assert_eq!(transcript.entries.len(), 12);
+        assert_eq!(transcript.entries[2], TranscriptEntry::Summary(SummaryEntry { summary: "Renaming config".to_string() }));
+        let TranscriptEntry::User(prompt) = &transcript.entries[0] else {
+            panic!("expected a user entry, got {:?}", transcript.entries[0]);
+        };
+        assert_eq!(prompt.message.content, vec![ContentBlock::Text { text: "rename the config".to_string() }]);
+    }
+
+    #[test]
+    fn test_last_turn() {
+        let transcript = sample();
+        let turn = transcript.last_turn();
+        assert_eq!(turn.prompt().as_deref(), Some("fix the tests"));
+        assert_eq!(turn.last_assistant_message().as_deref(), Some("All tests pass.\n\nAnything else?"));
+
+        let calls = turn.tool_calls();
+        assert_eq!(calls.iter().map(|call| call.name).collect::<Vec<_>>(), ["Edit", "Write", "Bash"]);
+        assert!(calls[1].result.as_ref().unwrap().is_error);
+        assert_eq!(turn.tools_used(), BTreeMap::from([("Bash".to_string(), 1), ("Edit".to_string(), 1), ("Write".to_string(), 1)]));
+
+        // The denied write changed nothing
+        assert_eq!(turn.files_changed(), ["/p/src/lib.rs"]);
+
+        // m1's usage is on two lines but counts once
+        let usage = turn.usage();
+        assert_eq!((usage.input_tokens, usage.output_tokens, usage.cache_read_input_tokens), (400, 60, 5000));
+        assert_eq!(usage.total(), 460);
+    }
+
+    #[test]
+    fn test_turn_without_final_text() {
+        let transcript = transcript(&[
+            json!({"type": "user", "message": {"content": "run it"}}),
+            json!({"type": "assistant", "message": {"content": [{"type": "tool_use", "id": "t1", "name": "Bash", "input": {}}]}}),
+            json!({"type": "user", "message": {"content": [{"type": "tool_result", "tool_use_id": "t1"}]}}),
+        ]);
+        assert_eq!(transcript.last_turn().last_assistant_message(), None);
+        assert_eq!(Transcript::default().last_turn().summary(), TurnSummary::default());
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = sample().last_turn().summary();
+        assert_eq!(summary.tool_call_count(), 3);
+        assert_eq!(summary.failed_tool_calls, 1);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["filesChanged"], json!(["/p/src/lib.rs"]));
+        assert_eq!(json["usage"]["inputTokens"], 400);
+
+        let text = summary.to_string();
+        assert!(text.contains("Tools: Bash ×1, Edit ×1, Write ×1 (1 failed)"));
+        assert!(text.ends_with("Response: All tests pass.\n\nAnything else?"));
+    }
+}
@@ -0,0 +1,160 @@
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use crate::hooks::paths::resolve_path;
+use crate::hooks::policy::{OnFailure, Policy, Validator};
+
+/// How much of a failing validator's output is passed on to Claude.
+const MAX_OUTPUT_CHARS: usize = 4000;
+
+/// A validator that rejected a hands-free edit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationFailure {
+    pub validator: String,
+    pub on_failure: OnFailure,
+    /// What went wrong, with the end of the validator's output
+    pub output: String,
+}
+
+/// Runs the policy's validators for a change to `file_path`, in order, and
+/// returns the first failure.
+pub fn validate_edit(policy: &Policy, file_path: &str, cwd: &Path) -> Option<ValidationFailure> {
+    let file = resolve_path(file_path, cwd);
+    policy.validators_for(file_path, cwd).find_map(|validator| {
+        eprintln!("Hands-free mode: Running validator '{}'", validator.name);
+        run_validator(validator, &file, cwd).err().map(|output| ValidationFailure {
+            validator: validator.name.clone(),
+            on_failure: validator.on_failure,
+            output,
+        })
+    })
+}
+
+/// Runs `validator` through `sh -c` in `cwd` with `$VOCAL_FILE` set;
+/// returns what went wrong when it fails or times out.
+pub fn run_validator(validator: &Validator, file: &Path, cwd: &Path) -> Result<(), String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&validator.command)
+        .current_dir(cwd)
+        .env("VOCAL_FILE", file)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not start: {}", e))?;
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let deadline = Instant::now() + validator.timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            Ok(None) => {
+                // Processes it started may keep the pipes open, so the output is left behind
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", validator.timeout.as_secs()));
+            }
+            Err(e) => return Err(format!("could not be waited for: {}", e)),
+        }
+    };
+
+    if status.success() {
+        return Ok(());
+    }
+    let output: String = [stdout, stderr]
+        .into_iter()
+        .flatten()
+        .filter_map(|reader| reader.join().ok())
+        .collect();
+    Err(format!("exited with {}\n{}", status, tail(output.trim(), MAX_OUTPUT_CHARS)))
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        let _ = pipe.read_to_string(&mut output);
+        output
+    })
+}
+
+/// The last `max_chars` of `text`; compiler errors usually end with a summary.
+fn tail(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let rest: String = text.chars().skip(count - max_chars).collect();
+    format!("…{}", rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::policy::{PolicyFile, PolicyLayer};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn policy(validators: &str) -> Policy {
+        let file: PolicyFile = toml::from_str(validators).unwrap();
+        let layer = PolicyLayer::from_file(PathBuf::from("policy.toml"), file).unwrap();
+        Policy { validators: layer.validators.clone(), ..Policy::new(vec![layer]) }
+    }
+
+    #[test]
+    fn test_first_failing_validator_is_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let cwd = temp_dir.path();
+        std::fs::write(cwd.join("lib.rs"), "fn main() { \n").unwrap();
+
+        let checks = policy(
+            r#"
+            [[validators]]
+            name = "markdown only"
+            command = "exit 1"
+            paths = ["**/*.md"]
+
+            [[validators]]
+            name = "file exists"
+            command = 'test -f "$VOCAL_FILE"'
+
+            [[validators]]
+            name = "trailing whitespace"
+            command = 'echo checking; ! grep -n " $" "$VOCAL_FILE"'
+            on_failure = "rollback"
+
+            [[validators]]
+            name = "never reached"
+            command = "exit 1"
+            "#,
+        );
+
+        let failure = validate_edit(&checks, "lib.rs", cwd).unwrap();
+        assert_eq!(failure.validator, "trailing whitespace");
+        assert_eq!(failure.on_failure, OnFailure::Rollback);
+        assert!(failure.output.ends_with("checking\n1:fn main() {"), "{}", failure.output);
+
+        std::fs::write(cwd.join("lib.rs"), "fn main() {}\n").unwrap();
+        let passing = policy("[[validators]]\ncommand = 'test -f \"$VOCAL_FILE\"'\n");
+        assert_eq!(validate_edit(&passing, "lib.rs", cwd), None);
+    }
+
+    #[test]
+    fn test_slow_validator_times_out() {
+        let slow = policy("[[validators]]\ncommand = \"sleep 5\"\ntimeout_secs = 0\n");
+        let started = Instant::now();
+        let failure = validate_edit(&slow, "lib.rs", Path::new("/tmp")).unwrap();
+        assert_eq!(failure.output, "timed out after 0s");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_tail() {
+        assert_eq!(tail("short", 10), "short");
+        assert_eq!(tail("a long line", 4), "…line");
+    }
+}
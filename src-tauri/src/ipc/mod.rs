@@ -0,0 +1,94 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::approval;
+use crate::commands;
+use crate::config::Config;
+use crate::hooks::ipc::{self, HandsFreeStatus, IpcCall, IpcHandler, IpcServer};
+use crate::secrets::SecretName;
+use crate::speech;
+
+/// Answers the `vocal hook` commands on the app's side of the socket.
+struct AppHandler {
+    app: AppHandle,
+}
+
+impl AppHandler {
+    fn emit(&self, event: &str, payload: impl serde::Serialize + Clone) -> Result<(), String> {
+        self.app
+            .emit(event, payload)
+            .map_err(|e| format!("Failed to emit {} event: {}", event, e))
+    }
+}
+
+impl IpcHandler for AppHandler {
+    fn handle(&self, call: IpcCall) -> Result<Value, String> {
+        match call {
+            IpcCall::HookEvent(report) => {
+                self.emit("hook-event", report)?;
+                Ok(Value::Null)
+            }
+            IpcCall::RestartRecording(report) => {
+                println!("Session {} stopped - restarting recording", report.hook.session_id);
+                // Answers the hook right away; reading the response out takes a while
+                let app = self.app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Some(message) = report.last_message {
+                        if let Err(e) = read_back(&app, &message).await {
+                            eprintln!("Failed to read out Claude's response: {}", e);
+                        }
+                    }
+                    if let Err(e) = app.emit("restart-recording", ()) {
+                        eprintln!("Failed to emit restart-recording event: {}", e);
+                    }
+                });
+                Ok(Value::Null)
+            }
+            // Blocks this connection until the user answers or the approval times out
+            IpcCall::Decide(request) => Ok(json!(approval::request(&self.app, request))),
+            IpcCall::GetState => Ok(json!(HandsFreeStatus::from_files())),
+        }
+    }
+}
+
+/// Reads Claude's final response out, condensed by the refinement backend
+/// when configured.
+async fn read_back(app: &AppHandle, message: &str) -> Result<(), String> {
+    let config = crate::current_config(app);
+    if !config.speech.readback {
+        return Ok(());
+    }
+
+    let text = if config.speech.summarize {
+        summarize(&config, message).await.unwrap_or_else(|e| {
+            eprintln!("Failed to summarize Claude's response, reading it in full: {}", e);
+            message.to_string()
+        })
+    } else {
+        message.to_string()
+    };
+    let text = speech::prepare_readback(&text, config.speech.max_chars);
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let speaker = config.speech.build()?;
+    println!("Reading out Claude's response via {}", speaker.name());
+    tauri::async_runtime::spawn_blocking(move || speaker.speak(&text))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+async fn summarize(config: &Config, message: &str) -> Result<String, String> {
+    let api_key = commands::lookup_api_key(SecretName::RefinementApiKey).await?;
+    let refiner = config.refinement.build(&api_key);
+    refiner.refine(config.refinement.request(speech::SUMMARY_PROMPT, message)).await
+}
+
+/// Starts serving `~/.vocal/vocal.sock` for as long as the app runs.
+pub fn start(app: &AppHandle) -> std::io::Result<()> {
+    let server = IpcServer::start(&ipc::socket_path(), AppHandler { app: app.clone() })?;
+    println!("Listening for hooks on {}", server.path().display());
+    app.manage(server);
+    Ok(())
+}
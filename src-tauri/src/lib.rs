@@ -1,25 +1,94 @@
 use tauri::{AppHandle, Manager, RunEvent, WindowEvent, Emitter};
-use tauri::{menu::{Menu, MenuItem}, tray::TrayIconBuilder};
-use serde::{Deserialize, Serialize};
+use tauri::{menu::{Menu, MenuItem, Submenu}, tray::TrayIconBuilder};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
+use serde::Serialize;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
+mod approval;
 mod automation;
 mod commands;
+mod config;
+mod history;
 mod hooks;
+mod ipc;
+mod refinement;
+mod secrets;
+mod speech;
+mod transcription;
+mod watcher;
+
+use config::Config;
+use secrets::SecretName;
+use transcription::AudioClip;
 
 // Store the current shortcut to unregister it later
 static CURRENT_SHORTCUT: Mutex<Option<String>> = Mutex::new(None);
 
-#[derive(Debug, Serialize, Deserialize)]
-struct TranscriptionResponse {
+// Cancellation handle for the streaming refinement in flight, tagged with its id
+static ACTIVE_REFINEMENT: Mutex<Option<(u64, tokio::sync::oneshot::Sender<()>)>> = Mutex::new(None);
+static NEXT_REFINEMENT_ID: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+struct RefinementDelta {
+    delta: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RefinementComplete {
     text: String,
+    cancelled: bool,
+}
+
+const TRAY_ID: &str = "main";
+// Number of recent prompts offered in the tray's resend submenu
+const TRAY_RECENT_PROMPTS: usize = 5;
+const TRAY_LABEL_CHARS: usize = 48;
+
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let show_item = MenuItem::with_id(app, "show", "Show Dictation Window", true, None::<&str>)?;
+    let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit Vocal", true, None::<&str>)?;
+
+    let recent = history::HistoryStore::open()
+        .recent_prompts(TRAY_RECENT_PROMPTS)
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to read recent prompts for tray menu: {}", e);
+            Vec::new()
+        });
+    let resend_menu = Submenu::with_id(app, "resend", "Resend Recent Prompt", !recent.is_empty())?;
+    for entry in &recent {
+        let label = history::preview(entry.refined_prompt.as_deref().unwrap_or_default(), TRAY_LABEL_CHARS);
+        let item = MenuItem::with_id(app, format!("resend-{}", entry.id), label, true, None::<&str>)?;
+        resend_menu.append(&item)?;
+    }
+
+    Menu::with_items(app, &[&show_item, &resend_menu, &settings_item, &quit_item])
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct RefinementResponse {
-    refined_text: String,
+/// Rebuilds the tray menu so the resend submenu lists the latest prompts.
+pub(crate) fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_tray_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                eprintln!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to build tray menu: {}", e),
+    }
+}
+
+/// Snapshot of the configuration loaded at startup and updated by `set_config`.
+pub(crate) fn current_config(app: &AppHandle) -> Config {
+    app.state::<Mutex<Config>>()
+        .lock()
+        .map(|config| config.clone())
+        .unwrap_or_default()
 }
 
 #[tauri::command]
@@ -98,109 +167,149 @@ async fn show_settings_window(app: AppHandle) -> Result<(), String> {
 }
 
 #[tauri::command]
-async fn transcribe_audio(audio_data: Vec<u8>, api_key: String) -> Result<String, String> {
+async fn transcribe_audio(
+    app: AppHandle,
+    audio_data: Vec<u8>,
+    mime_type: Option<String>,
+) -> Result<String, String> {
     println!("Starting transcription - audio data size: {} bytes", audio_data.len());
-    let client = reqwest::Client::new();
-    
-    let form = reqwest::multipart::Form::new()
-        .part("file", reqwest::multipart::Part::bytes(audio_data)
-            .file_name("audio.webm")
-            .mime_str("audio/webm").unwrap())
-        .text("model", "whisper-large-v3")
-        .text("response_format", "json");
-
-    println!("Sending transcription request to Groq API...");
-    let response = client
-        .post("https://api.groq.com/openai/v1/audio/transcriptions")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| {
-            println!("Transcription request failed: {}", e);
-            format!("Request failed: {}", e)
-        })?;
+    let config = current_config(&app);
+    let api_key = commands::lookup_api_key(SecretName::TranscriptionApiKey).await?;
+    let transcriber = config.transcription.build(&api_key)?;
+    let audio = match mime_type {
+        Some(mime_type) => AudioClip::new(audio_data, &mime_type),
+        None => AudioClip::webm(audio_data),
+    };
 
-    let status = response.status();
-    println!("Transcription response status: {}", status);
-    
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
-        println!("Transcription API error ({}): {}", status, error_body);
-        return Err(format!("Transcription failed ({}): {}", status, error_body));
-    }
-    
-    let transcription: TranscriptionResponse = response
-        .json()
+    println!("Sending transcription request to {}...", transcriber.name());
+    let started = Instant::now();
+    let text = transcriber
+        .transcribe(audio)
         .await
         .map_err(|e| {
-            println!("Failed to parse transcription response: {}", e);
-            format!("Failed to parse response: {}", e)
+            println!("Transcription via {} failed: {}", transcriber.name(), e);
+            e
         })?;
 
-    println!("Raw transcription result: '{}'", transcription.text);
-    Ok(transcription.text)
+    println!("Raw transcription result: '{}'", text);
+    commands::record_transcription(&text, transcriber.name(), started.elapsed());
+    Ok(text)
 }
 
 #[tauri::command]
-async fn refine_prompt(text: String, api_key: String, system_prompt: Option<String>) -> Result<String, String> {
+async fn download_whisper_model(model: String) -> Result<String, String> {
+    println!("Downloading local Whisper model '{}'...", model);
+    let path = transcription::models::download_model(
+        transcription::models::WHISPER_MODEL_BASE_URL,
+        &model,
+        &transcription::models::models_dir(),
+    )
+    .await
+    .map_err(|e| {
+        println!("Failed to download Whisper model '{}': {}", model, e);
+        e
+    })?;
+
+    println!("Whisper model saved to {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn refine_prompt(app: AppHandle, text: String) -> Result<String, String> {
     println!("Starting prompt refinement for text: '{}'", text);
-    let client = reqwest::Client::new();
-    let system_text = system_prompt.unwrap();
+    let config = current_config(&app);
+    let system_text = refinement::require_system_prompt(Some(config.system_prompt))?;
     println!("Using system prompt length: {} chars", system_text.len());
-    
-    let request_body = serde_json::json!({
-        "model": "claude-sonnet-4-20250514",
-        "max_tokens": 64000,
-        "system": system_text,
-        "messages": [
-            {
-                "role": "user",
-                "content": text
-            }
-        ]
-    });
-
-    println!("Sending refinement request to Claude API...");
-    let response = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("x-api-key", api_key)
-        .header("anthropic-version", "2023-06-01")
-        .header("content-type", "application/json")
-        .json(&request_body)
-        .send()
+
+    let api_key = commands::lookup_api_key(SecretName::RefinementApiKey).await?;
+    let refiner = config.refinement.build(&api_key);
+
+    println!("Sending refinement request to {}...", refiner.name());
+    let started = Instant::now();
+    let refined_text = refiner
+        .refine(config.refinement.request(&system_text, &text))
         .await
         .map_err(|e| {
-            println!("Claude request failed: {}", e);
-            format!("Request failed: {}", e)
+            println!("Refinement via {} failed: {}", refiner.name(), e);
+            e
         })?;
 
-    let status = response.status();
-    println!("Claude response status: {}", status);
-    
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
-        println!("Claude API error ({}): {}", status, error_body);
-        return Err(format!("Claude request failed ({}): {}", status, error_body));
+    println!("Refined prompt result: '{}'", refined_text);
+    commands::record_refinement(&text, &refined_text, refiner.name(), started.elapsed());
+    refresh_tray_menu(&app);
+    Ok(refined_text)
+}
+
+#[tauri::command]
+async fn refine_prompt_stream(app: AppHandle, text: String) -> Result<String, String> {
+    println!("Starting streaming prompt refinement for text: '{}'", text);
+    let config = current_config(&app);
+    let system_text = refinement::require_system_prompt(Some(config.system_prompt))?;
+    println!("Using system prompt length: {} chars", system_text.len());
+
+    let api_key = commands::lookup_api_key(SecretName::RefinementApiKey).await?;
+    let refiner = config.refinement.build(&api_key);
+
+    // Registering a new refinement drops the previous sender, which cancels it
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    let id = NEXT_REFINEMENT_ID.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut active) = ACTIVE_REFINEMENT.lock() {
+        *active = Some((id, cancel_tx));
     }
-    
-    let response_json: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| {
-            println!("Failed to parse Claude response: {}", e);
-            format!("Failed to parse response: {}", e)
-        })?;
 
-    let refined_text = response_json["content"][0]["text"]
-        .as_str()
-        .ok_or_else(|| {
-            println!("No content found in Claude response: {:?}", response_json);
-            "No content found in response".to_string()
-        })?;
+    let delta_app = app.clone();
+    let on_delta = move |delta: &str| {
+        let payload = RefinementDelta { delta: delta.to_string() };
+        if let Err(e) = delta_app.emit_to("main", "refinement-delta", payload) {
+            println!("Failed to emit refinement delta: {}", e);
+        }
+    };
 
-    println!("Refined prompt result: '{}'", refined_text);
-    Ok(refined_text.to_string())
+    println!("Streaming refinement from {}...", refiner.name());
+    let started = Instant::now();
+    let result = tokio::select! {
+        result = refiner.refine_stream(config.refinement.request(&system_text, &text), &on_delta) => result.map(Some),
+        _ = cancel_rx => Ok(None),
+    };
+
+    if let Ok(mut active) = ACTIVE_REFINEMENT.lock() {
+        if matches!(active.as_ref(), Some((active_id, _)) if *active_id == id) {
+            *active = None;
+        }
+    }
+
+    match result {
+        Ok(Some(refined_text)) => {
+            println!("Refined prompt result: '{}'", refined_text);
+            commands::record_refinement(&text, &refined_text, refiner.name(), started.elapsed());
+            refresh_tray_menu(&app);
+            let payload = RefinementComplete { text: refined_text.clone(), cancelled: false };
+            app.emit_to("main", "refinement-complete", payload).map_err(|e| e.to_string())?;
+            Ok(refined_text)
+        }
+        Ok(None) => {
+            println!("Streaming refinement cancelled");
+            let payload = RefinementComplete { text: String::new(), cancelled: true };
+            app.emit_to("main", "refinement-complete", payload).map_err(|e| e.to_string())?;
+            Err("Refinement cancelled".to_string())
+        }
+        Err(e) => {
+            println!("Streaming refinement via {} failed: {}", refiner.name(), e);
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command]
+async fn cancel_refinement() -> Result<bool, String> {
+    let active = ACTIVE_REFINEMENT.lock().map_err(|e| e.to_string())?.take();
+    match active {
+        Some((_, cancel_tx)) => {
+            println!("Cancelling streaming refinement");
+            Ok(cancel_tx.send(()).is_ok())
+        }
+        None => Ok(false),
+    }
 }
 
 #[tauri::command]
@@ -216,10 +325,8 @@ async fn copy_to_clipboard(text: String, app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
-async fn update_global_shortcut(shortcut: String, app: AppHandle) -> Result<(), String> {
-    println!("Updating global shortcut to: {}", shortcut);
-    
+/// Replaces the registered dictation shortcut with `shortcut`.
+pub(crate) fn register_dictation_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
     // Unregister the current shortcut
     let global_shortcut = app.global_shortcut();
     if let Ok(mut current) = CURRENT_SHORTCUT.lock() {
@@ -228,12 +335,12 @@ async fn update_global_shortcut(shortcut: String, app: AppHandle) -> Result<(),
                 println!("Warning: Failed to unregister old shortcut '{}': {}", old_shortcut, e);
             }
         }
-        *current = Some(shortcut.clone());
+        *current = Some(shortcut.to_string());
     }
     
     // Register the new shortcut
     let app_handle = app.clone();
-    global_shortcut.on_shortcut(shortcut.as_str(), move |_app, _event, _shortcut| {
+    global_shortcut.on_shortcut(shortcut, move |_app, _event, _shortcut| {
         let app_handle = app_handle.clone();
         tauri::async_runtime::spawn(async move {
             if let Err(e) = show_dictation_window(app_handle).await {
@@ -241,10 +348,15 @@ async fn update_global_shortcut(shortcut: String, app: AppHandle) -> Result<(),
             }
         });
     }).map_err(|e| {
-        println!("Failed to register new shortcut '{}': {}", shortcut, e);
+        println!("Failed to register shortcut '{}': {}", shortcut, e);
         format!("Failed to register shortcut: {}", e)
-    })?;
-    
+    })
+}
+
+#[tauri::command]
+async fn update_global_shortcut(shortcut: String, app: AppHandle) -> Result<(), String> {
+    println!("Updating global shortcut to: {}", shortcut);
+    register_dictation_shortcut(&app, &shortcut)?;
     println!("Successfully updated global shortcut to: {}", shortcut);
     Ok(())
 }
@@ -258,6 +370,18 @@ pub fn run() {
         .setup(|app| {
             let app_handle = app.handle().clone();
             
+            // Move API keys saved by older versions out of config.toml
+            match secrets::Secrets::open().migrate_legacy_config(&config::config_path()) {
+                Ok(0) => {}
+                Ok(count) => println!("Moved {} API key(s) from config.toml into the secrets store", count),
+                Err(e) => eprintln!("Failed to migrate API keys out of config.toml: {}", e),
+            }
+
+            let config = Config::load().unwrap_or_else(|e| {
+                eprintln!("Failed to load configuration, using defaults: {}", e);
+                Config::default()
+            });
+            
             // Hide dock icon on macOS
             #[cfg(target_os = "macos")]
             {
@@ -265,13 +389,10 @@ pub fn run() {
             }
             
             // Create tray menu
-            let quit_item = MenuItem::with_id(app, "quit", "Quit Vocal", true, None::<&str>)?;
-            let show_item = MenuItem::with_id(app, "show", "Show Dictation Window", true, None::<&str>)?;
-            let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &settings_item, &quit_item])?;
+            let menu = build_tray_menu(&app_handle)?;
             
             // Create tray icon
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
                 .show_menu_on_left_click(true)
@@ -296,27 +417,34 @@ pub fn run() {
                                 }
                             });
                         }
-                        _ => {}
+                        id => {
+                            if let Some(entry_id) = id.strip_prefix("resend-").map(str::to_string) {
+                                tauri::async_runtime::spawn(async move {
+                                    if let Err(e) = commands::resend_history_entry(&entry_id) {
+                                        eprintln!("Failed to resend history entry {}: {}", entry_id, e);
+                                    }
+                                });
+                            }
+                        }
                     }
                 })
                 .build(app)?;
             
-            // Register global shortcut with default value
-            let default_shortcut = "CommandOrControl+Shift+V";
-            
-            // Store the initial shortcut
-            if let Ok(mut current) = CURRENT_SHORTCUT.lock() {
-                *current = Some(default_shortcut.to_string());
+            // Register the saved global shortcut, falling back to the default
+            if let Err(e) = register_dictation_shortcut(&app_handle, &config.hotkey) {
+                eprintln!("Failed to register saved shortcut '{}': {}", config.hotkey, e);
+                register_dictation_shortcut(&app_handle, config::DEFAULT_HOTKEY)?;
             }
             
-            app.global_shortcut().on_shortcut(default_shortcut, move |_app, _event, _shortcut| {
-                let app_handle = app_handle.clone();
-                tauri::async_runtime::spawn(async move {
-                    if let Err(e) = show_dictation_window(app_handle).await {
-                        eprintln!("Failed to show dictation window: {}", e);
-                    }
-                });
-            }).unwrap();
+            app.manage(Mutex::new(config));
+            
+            // Hooks talk to the app over the socket and fall back to state files
+            if let Err(e) = ipc::start(&app_handle) {
+                eprintln!("Failed to start hook socket: {}", e);
+            }
+            if let Err(e) = watcher::start(&app_handle) {
+                eprintln!("Failed to watch hands-free state files: {}", e);
+            }
             
             Ok(())
         })
@@ -325,22 +453,42 @@ pub fn run() {
             hide_dictation_window,
             show_settings_window,
             transcribe_audio,
+            download_whisper_model,
             refine_prompt,
+            refine_prompt_stream,
+            cancel_refinement,
             copy_to_clipboard,
             update_global_shortcut,
             commands::inject_prompt_to_claude_session,
             commands::activate_hands_free_mode,
             commands::deactivate_hands_free_mode,
             commands::get_hands_free_status,
+            commands::get_hands_free_stop_reason,
+            commands::list_sessions,
+            commands::get_session_summary,
+            commands::set_target_session,
+            commands::get_target_session,
             commands::trigger_emergency_stop,
+            commands::clear_emergency_stop,
+            commands::check_cycle_trigger,
             commands::get_terminal_info,
             commands::is_terminal_active,
-            commands::check_cycle_trigger,
+            commands::answer_approval,
             commands::install_cli_symlink,
             commands::check_cli_installed,
             commands::get_cli_install_command,
             commands::get_detailed_cli_status,
-            commands::open_terminal_with_command
+            commands::open_terminal_with_command,
+            commands::get_config,
+            commands::set_config,
+            commands::get_default_config,
+            commands::get_secret_status,
+            commands::set_secret,
+            commands::delete_secret,
+            commands::list_history,
+            commands::search_history,
+            commands::delete_history_entry,
+            commands::resend_recent_prompt
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
@@ -2,6 +2,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod cli;
+mod history;
 mod hooks;
 mod automation;
 
@@ -34,16 +35,197 @@ fn handle_cli_command(cli: cli::Cli) -> Result<(), Box<dyn std::error::Error>> {
             hooks::install_hooks()?;
         }
         cli::Commands::Hook { hook_type } => {
-            let ctx = hooks::HookContext::from_stdin()?;
+            let ctx = hooks::HookContext::from_stdin();
+            // Lets the running app follow every session; a no-op without it
+            if let Ok(ctx) = &ctx {
+                hooks::ipc::report_hook_event(ctx);
+            }
             
             match hook_type {
-                cli::HookCommands::PreToolUse => hooks::handle_pre_tool_use(&ctx)?,
-                cli::HookCommands::PostToolUse => hooks::handle_post_tool_use(&ctx)?,
-                cli::HookCommands::Stop => hooks::handle_stop(&ctx)?,
-                cli::HookCommands::UserPromptSubmit => hooks::handle_user_prompt_submit(&ctx)?,
+                cli::HookCommands::PreToolUse => {
+                    // Exit code 1 would let the tool run; fail closed while hands-free
+                    let response = match ctx.and_then(|ctx| hooks::handle_pre_tool_use(&ctx)) {
+                        Ok(response) => response,
+                        Err(e) if hooks::is_hands_free_active() => {
+                            hooks::HookResponse::Blocking(format!("Vocal safety hook failed: {}", e))
+                        }
+                        Err(e) => return Err(e),
+                    };
+                    emit_hook_response(response)?;
+                }
+                cli::HookCommands::PostToolUse => emit_hook_response(hooks::handle_post_tool_use(&ctx?)?)?,
+                cli::HookCommands::Stop => emit_hook_response(hooks::handle_stop(&ctx?)?)?,
+                cli::HookCommands::UserPromptSubmit => hooks::handle_user_prompt_submit(&ctx?)?,
+                cli::HookCommands::Notification => hooks::handle_notification(&ctx?)?,
+                cli::HookCommands::SubagentStop => hooks::handle_subagent_stop(&ctx?)?,
+                cli::HookCommands::PreCompact => hooks::handle_pre_compact(&ctx?)?,
+                cli::HookCommands::SessionStart => hooks::handle_session_start(&ctx?)?,
+                cli::HookCommands::SessionEnd => hooks::handle_session_end(&ctx?)?,
+            }
+        }
+        cli::Commands::History { search, limit, json, delete } => {
+            let store = history::HistoryStore::open();
+            
+            if let Some(id) = delete {
+                if !store.delete(&id)? {
+                    return Err(format!("No history entry with id {}", id).into());
+                }
+                println!("Deleted history entry {}", id);
+                return Ok(());
+            }
+            
+            let entries = match search {
+                Some(query) => store.search(&query, limit)?,
+                None => store.list(limit, 0)?,
+            };
+            
+            for entry in &entries {
+                if json {
+                    println!("{}", serde_json::to_string(entry)?);
+                } else {
+                    println!("{}", entry.summary());
+                }
+            }
+            if entries.is_empty() && !json {
+                println!("No history entries found in {}", store.path().display());
+            }
+        }
+        cli::Commands::Policy { command } => match command {
+            cli::PolicyCommands::Check { tool, input, cwd } => {
+                let tool_input: serde_json::Value = serde_json::from_str(&input)
+                    .map_err(|e| format!("--input is not valid JSON: {}", e))?;
+                let cwd = match cwd {
+                    Some(cwd) => cwd,
+                    None => env::current_dir()?,
+                };
+                
+                let policy = hooks::Policy::load(&cwd)?;
+                for layer in &policy.layers {
+                    println!("Loaded {} rule(s) from {}", layer.len(), layer.source.display());
+                }
+                
+                let decision = hooks::SafetyRules::evaluate_with_policy(&policy, &tool, &tool_input, &cwd);
+                println!("{}: {}", decision.level.as_str(), decision.reason);
+            }
+        },
+        cli::Commands::Resend { n, edit, dry_run } => {
+            let mut prompt = history::HistoryStore::open()
+                .recent_prompt(n)?
+                .ok_or_else(|| format!("No refined prompt #{} in history", n))?;
+            
+            if edit {
+                prompt = edit_in_editor(&prompt)?;
+            }
+            
+            if dry_run {
+                println!("{}", prompt);
+                return Ok(());
+            }
+            
+            automation::terminal::TerminalInjector::inject_text_to_claude_session(&prompt)?;
+            println!("Re-sent prompt #{} to Claude Code session", n);
+        }
+        cli::Commands::Transcript { command } => match command {
+            cli::TranscriptCommands::Summarize { path, json } => {
+                let transcript = hooks::transcript::Transcript::read(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                let summary = transcript.last_turn().summary();
+                
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&summary)?);
+                } else {
+                    println!("{}", summary);
+                    let usage = transcript.usage();
+                    println!("Session tokens: {} in, {} out", usage.input_tokens, usage.output_tokens);
+                }
+            }
+        },
+        cli::Commands::Undo { n, session, force, dry_run } => {
+            let store = hooks::snapshots::SnapshotStore::open();
+            
+            if dry_run {
+                let edits = store.applied(session.as_deref())?;
+                for snapshot in edits.iter().take(n) {
+                    println!("{}", snapshot);
+                }
+                if edits.is_empty() {
+                    println!("No hands-free edits to undo in {}", store.dir().display());
+                }
+                return Ok(());
+            }
+            
+            let undone = store.undo(n, session.as_deref(), force)?;
+            if undone.is_empty() {
+                println!("No hands-free edits to undo in {}", store.dir().display());
+            } else {
+                println!("Rolled back {} edit(s):", undone.len());
+            }
+            for snapshot in &undone {
+                println!("  {}", snapshot);
+            }
+        }
+        cli::Commands::Audit { command } => {
+            let log = hooks::audit::AuditLog::open();
+            let (entries, json) = match command {
+                cli::AuditCommands::Tail { limit, json } => (log.tail(limit)?, json),
+                cli::AuditCommands::Query { session, tool, since, limit, json } => {
+                    let since = since
+                        .map(|since| hooks::audit::parse_since(&since, chrono::Utc::now()))
+                        .transpose()?;
+                    let query = hooks::audit::AuditQuery { session, tool, since };
+                    (log.query(&query, limit)?, json)
+                }
+            };
+            
+            for entry in &entries {
+                if json {
+                    println!("{}", serde_json::to_string(entry)?);
+                } else {
+                    println!("{}", entry);
+                }
+            }
+            if entries.is_empty() && !json {
+                println!("No audit entries found in {}", log.path().display());
             }
         }
     }
     
     Ok(())
 }
+
+/// Prints a hook's answer and exits with the code Claude Code expects.
+fn emit_hook_response(response: hooks::HookResponse) -> Result<(), Box<dyn std::error::Error>> {
+    let code = response.emit()?;
+    if code != hooks::EXIT_SUCCESS {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Opens `text` in the user's editor and returns the saved result.
+fn edit_in_editor(text: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = env::temp_dir().join(format!("vocal-resend-{}.md", std::process::id()));
+    std::fs::write(&path, text)?;
+    
+    // The editor variable may carry arguments, e.g. "code --wait"
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    
+    if !status?.success() {
+        return Err(format!("Editor '{}' exited with an error", editor).into());
+    }
+    let edited = edited?.trim().to_string();
+    if edited.is_empty() {
+        return Err("Prompt is empty, nothing sent".into());
+    }
+    Ok(edited)
+}
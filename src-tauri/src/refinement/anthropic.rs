@@ -0,0 +1,270 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::sse::SseParser;
+use super::{RefinementRequest, Refiner};
+
+pub const ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+pub const ANTHROPIC_DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+pub const ANTHROPIC_DEFAULT_MAX_TOKENS: u32 = 64000;
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Anthropic Messages API.
+pub struct AnthropicRefiner {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl AnthropicRefiner {
+    pub fn new(base_url: &str, model: &str, api_key: &str) -> Self {
+        AnthropicRefiner {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    fn request_body(&self, request: &RefinementRequest<'_>) -> Value {
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": request.max_tokens,
+            "system": request.system_prompt,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": request.text
+                }
+            ]
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        body
+    }
+
+    async fn send(&self, body: &Value) -> Result<reqwest::Response, String> {
+        let response = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("content-type", "application/json")
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
+            return Err(format!("Claude request failed ({}): {}", status, error_body));
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Refiner for AnthropicRefiner {
+    fn name(&self) -> &str {
+        "Claude"
+    }
+
+    async fn refine(&self, request: RefinementRequest<'_>) -> Result<String, String> {
+        let response = self.send(&self.request_body(&request)).await?;
+
+        let response_json: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        response_json["content"][0]["text"]
+            .as_str()
+            .map(|text| text.to_string())
+            .ok_or_else(|| "No content found in response".to_string())
+    }
+
+    async fn refine_stream(
+        &self,
+        request: RefinementRequest<'_>,
+        on_delta: &(dyn for<'d> Fn(&'d str) + Send + Sync),
+    ) -> Result<String, String> {
+        let mut body = self.request_body(&request);
+        body["stream"] = json!(true);
+        let mut response = self.send(&body).await?;
+
+        let mut parser = SseParser::default();
+        let mut text = String::new();
+
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Claude stream interrupted: {}", e))?
+        {
+            for event in parser.push(&chunk) {
+                let payload: Value = match serde_json::from_str(&event.data) {
+                    Ok(payload) => payload,
+                    Err(_) => continue,
+                };
+
+                match payload["type"].as_str() {
+                    Some("content_block_delta") => {
+                        if let Some(delta) = payload["delta"]["text"].as_str() {
+                            text.push_str(delta);
+                            on_delta(delta);
+                        }
+                    }
+                    Some("error") => {
+                        let message = payload["error"]["message"].as_str().unwrap_or("unknown error");
+                        return Err(format!("Claude stream failed: {}", message));
+                    }
+                    Some("message_stop") => return Ok(text),
+                    _ => {}
+                }
+            }
+        }
+
+        if text.is_empty() {
+            return Err("No content found in response".to_string());
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_refine_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/messages")
+            .match_header("x-api-key", "sk-ant")
+            .match_header("anthropic-version", ANTHROPIC_VERSION)
+            .match_body(Matcher::PartialJson(json!({
+                "model": "claude-test",
+                "max_tokens": 512,
+                "system": "Clean up",
+                "temperature": 0.0,
+                "messages": [{"role": "user", "content": "um fix the bug"}]
+            })))
+            .with_status(200)
+            .with_body(r#"{"content":[{"type":"text","text":"Fix the bug."}]}"#)
+            .create_async()
+            .await;
+
+        let refiner = AnthropicRefiner::new(&format!("{}/v1", server.url()), "claude-test", "sk-ant");
+        let refined = refiner
+            .refine(RefinementRequest {
+                system_prompt: "Clean up",
+                text: "um fix the bug",
+                temperature: Some(0.0),
+                max_tokens: 512,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(refined, "Fix the bug.");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_refine_stream_emits_deltas() {
+        let mut server = mockito::Server::new_async().await;
+        let body = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Run the \"}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"tests.\"}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+        let mock = server
+            .mock("POST", "/messages")
+            .match_body(Matcher::PartialJson(json!({"stream": true})))
+            .with_status(200)
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let refiner = AnthropicRefiner::new(&server.url(), ANTHROPIC_DEFAULT_MODEL, "sk-ant");
+        let deltas = std::sync::Mutex::new(Vec::new());
+        let refined = refiner
+            .refine_stream(
+                RefinementRequest {
+                    system_prompt: "Clean up",
+                    text: "run the tests",
+                    temperature: None,
+                    max_tokens: 64,
+                },
+                &|delta| deltas.lock().unwrap().push(delta.to_string()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(refined, "Run the tests.");
+        assert_eq!(*deltas.lock().unwrap(), vec!["Run the ", "tests."]);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_refine_stream_reports_error_events() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/messages")
+            .with_status(200)
+            .with_body("event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n")
+            .create_async()
+            .await;
+
+        let refiner = AnthropicRefiner::new(&server.url(), ANTHROPIC_DEFAULT_MODEL, "sk-ant");
+        let error = refiner
+            .refine_stream(
+                RefinementRequest {
+                    system_prompt: "Clean up",
+                    text: "text",
+                    temperature: None,
+                    max_tokens: 16,
+                },
+                &|_| {},
+            )
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("Overloaded"));
+    }
+
+    #[tokio::test]
+    async fn test_refine_reports_api_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/messages")
+            .with_status(529)
+            .with_body("overloaded")
+            .create_async()
+            .await;
+
+        let refiner = AnthropicRefiner::new(&server.url(), ANTHROPIC_DEFAULT_MODEL, "sk-ant");
+        let error = refiner
+            .refine(RefinementRequest {
+                system_prompt: "Clean up",
+                text: "text",
+                temperature: None,
+                max_tokens: 16,
+            })
+            .await
+            .unwrap_err();
+
+        assert!(error.contains("529"));
+    }
+}
@@ -0,0 +1,165 @@
+pub mod anthropic;
+pub mod ollama;
+pub mod openai;
+pub mod sse;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+pub use anthropic::*;
+pub use ollama::*;
+pub use openai::*;
+
+pub const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// Sampling settings shared by every refinement backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefinementRequest<'a> {
+    pub system_prompt: &'a str,
+    pub text: &'a str,
+    pub temperature: Option<f32>,
+    pub max_tokens: u32,
+}
+
+#[async_trait]
+pub trait Refiner: Send + Sync {
+    /// Human readable backend name used in logs.
+    fn name(&self) -> &str;
+
+    async fn refine(&self, request: RefinementRequest<'_>) -> Result<String, String>;
+
+    /// Streams the refinement, calling `on_delta` with each text fragment as it
+    /// arrives and returning the full text. Backends without streaming support
+    /// deliver the whole result as a single delta.
+    async fn refine_stream(
+        &self,
+        request: RefinementRequest<'_>,
+        on_delta: &(dyn for<'d> Fn(&'d str) + Send + Sync),
+    ) -> Result<String, String> {
+        let text = self.refine(request).await?;
+        on_delta(&text);
+        Ok(text)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RefinementProvider {
+    #[default]
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    #[serde(rename = "openai-compatible")]
+    OpenAiCompatible,
+    #[serde(rename = "ollama")]
+    Ollama,
+}
+
+/// Selects and parameterises the refinement backend.
+///
+/// Unset fields fall back to the provider defaults. `stream` makes the
+/// dictation window use `refine_prompt_stream`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RefinementConfig {
+    pub provider: RefinementProvider,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub stream: bool,
+}
+
+impl Default for RefinementConfig {
+    fn default() -> Self {
+        RefinementConfig {
+            provider: RefinementProvider::default(),
+            base_url: None,
+            model: None,
+            temperature: None,
+            max_tokens: None,
+            stream: true,
+        }
+    }
+}
+
+impl RefinementConfig {
+    pub fn build(&self, api_key: &str) -> Box<dyn Refiner> {
+        let base_url = self.base_url.as_deref().filter(|url| !url.trim().is_empty());
+        let model = self.model.as_deref().filter(|model| !model.trim().is_empty());
+
+        match self.provider {
+            RefinementProvider::Anthropic => Box::new(AnthropicRefiner::new(
+                base_url.unwrap_or(ANTHROPIC_BASE_URL),
+                model.unwrap_or(ANTHROPIC_DEFAULT_MODEL),
+                api_key,
+            )),
+            RefinementProvider::OpenAiCompatible => Box::new(OpenAiRefiner::new(
+                base_url.unwrap_or(OPENAI_CHAT_BASE_URL),
+                model.unwrap_or(OPENAI_CHAT_DEFAULT_MODEL),
+                api_key,
+            )),
+            RefinementProvider::Ollama => Box::new(OllamaRefiner::new(
+                base_url.unwrap_or(OLLAMA_BASE_URL),
+                model.unwrap_or(OLLAMA_DEFAULT_MODEL),
+            )),
+        }
+    }
+
+    pub fn max_tokens(&self) -> u32 {
+        self.max_tokens.unwrap_or(match self.provider {
+            RefinementProvider::Anthropic => ANTHROPIC_DEFAULT_MAX_TOKENS,
+            _ => DEFAULT_MAX_TOKENS,
+        })
+    }
+
+    pub fn request<'a>(&self, system_prompt: &'a str, text: &'a str) -> RefinementRequest<'a> {
+        RefinementRequest {
+            system_prompt,
+            text,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens(),
+        }
+    }
+}
+
+/// Rejects a missing or blank system prompt instead of sending an empty one.
+pub fn require_system_prompt(system_prompt: Option<String>) -> Result<String, String> {
+    system_prompt
+        .filter(|prompt| !prompt.trim().is_empty())
+        .ok_or_else(|| "No system prompt configured. Set one in Settings before refining.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_default_config_uses_anthropic() {
+        let config = RefinementConfig::default();
+        assert_eq!(config.build("key").name(), "Claude");
+        assert_eq!(config.max_tokens(), ANTHROPIC_DEFAULT_MAX_TOKENS);
+    }
+
+    #[test]
+    fn test_config_deserialization() {
+        let config: RefinementConfig = serde_json::from_value(json!({
+            "provider": "ollama",
+            "model": "qwen2.5-coder",
+            "temperature": 0.2,
+            "maxTokens": 1024
+        }))
+        .unwrap();
+
+        assert_eq!(config.provider, RefinementProvider::Ollama);
+        let request = config.request("system", "text");
+        assert_eq!(request.temperature, Some(0.2));
+        assert_eq!(request.max_tokens, 1024);
+    }
+
+    #[test]
+    fn test_missing_system_prompt_is_an_error() {
+        assert!(require_system_prompt(None).is_err());
+        assert!(require_system_prompt(Some("   ".to_string())).is_err());
+        assert_eq!(require_system_prompt(Some("Clean up".to_string())).unwrap(), "Clean up");
+    }
+}
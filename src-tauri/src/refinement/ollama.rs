@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{RefinementRequest, Refiner};
+
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+pub const OLLAMA_DEFAULT_MODEL: &str = "llama3.1";
+
+/// Local Ollama-style `/api/chat` server; nothing leaves the machine.
+pub struct OllamaRefiner {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaRefiner {
+    pub fn new(base_url: &str, model: &str) -> Self {
+        OllamaRefiner {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Refiner for OllamaRefiner {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    async fn refine(&self, request: RefinementRequest<'_>) -> Result<String, String> {
+        let mut options = json!({ "num_predict": request.max_tokens });
+        if let Some(temperature) = request.temperature {
+            options["temperature"] = json!(temperature);
+        }
+
+        let body = json!({
+            "model": self.model,
+            "stream": false,
+            "options": options,
+            "messages": [
+                { "role": "system", "content": request.system_prompt },
+                { "role": "user", "content": request.text }
+            ]
+        });
+
+        let response = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
+            return Err(format!("Ollama request failed ({}): {}", status, error_body));
+        }
+
+        let response_json: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        response_json["message"]["content"]
+            .as_str()
+            .map(|text| text.to_string())
+            .ok_or_else(|| "No content found in response".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_refine_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "llama-test",
+                "stream": false,
+                "options": {"num_predict": 128, "temperature": 0.5}
+            })))
+            .with_status(200)
+            .with_body(r#"{"message":{"role":"assistant","content":"Add a README."},"done":true}"#)
+            .create_async()
+            .await;
+
+        let refiner = OllamaRefiner::new(&server.url(), "llama-test");
+        let refined = refiner
+            .refine(RefinementRequest {
+                system_prompt: "Clean up",
+                text: "like add a readme",
+                temperature: Some(0.5),
+                max_tokens: 128,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(refined, "Add a README.");
+        mock.assert_async().await;
+    }
+}
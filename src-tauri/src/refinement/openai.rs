@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::{RefinementRequest, Refiner};
+
+pub const OPENAI_CHAT_BASE_URL: &str = "https://api.openai.com/v1";
+pub const OPENAI_CHAT_DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// Any server implementing the OpenAI `/chat/completions` endpoint.
+pub struct OpenAiRefiner {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl OpenAiRefiner {
+    pub fn new(base_url: &str, model: &str, api_key: &str) -> Self {
+        OpenAiRefiner {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Refiner for OpenAiRefiner {
+    fn name(&self) -> &str {
+        "OpenAI-compatible server"
+    }
+
+    async fn refine(&self, request: RefinementRequest<'_>) -> Result<String, String> {
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": request.max_tokens,
+            "messages": [
+                { "role": "system", "content": request.system_prompt },
+                { "role": "user", "content": request.text }
+            ]
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+
+        let mut http_request = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&body);
+
+        // Self-hosted servers frequently run without authentication
+        if !self.api_key.is_empty() {
+            http_request = http_request.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+
+        let response = http_request
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
+            return Err(format!("Refinement request failed ({}): {}", status, error_body));
+        }
+
+        let response_json: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        response_json["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|text| text.to_string())
+            .ok_or_else(|| "No content found in response".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_refine_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", "Bearer sk-test")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "gpt-test",
+                "max_tokens": 256,
+                "messages": [
+                    {"role": "system", "content": "Clean up"},
+                    {"role": "user", "content": "so uh run the tests"}
+                ]
+            })))
+            .with_status(200)
+            .with_body(r#"{"choices":[{"message":{"role":"assistant","content":"Run the tests."}}]}"#)
+            .create_async()
+            .await;
+
+        let refiner = OpenAiRefiner::new(&format!("{}/v1/", server.url()), "gpt-test", "sk-test");
+        let refined = refiner
+            .refine(RefinementRequest {
+                system_prompt: "Clean up",
+                text: "so uh run the tests",
+                temperature: None,
+                max_tokens: 256,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(refined, "Run the tests.");
+        mock.assert_async().await;
+    }
+}
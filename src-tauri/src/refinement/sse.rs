@@ -0,0 +1,81 @@
+/// A single server-sent event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+}
+
+/// Incremental `text/event-stream` parser. Network chunks can split events
+/// (and multi-byte characters) anywhere, so bytes are buffered until a blank
+/// line terminates the event.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend(chunk.iter().filter(|&&byte| byte != b'\r'));
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.windows(2).position(|window| window == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..end + 2).collect();
+            if let Some(event) = Self::parse_block(&String::from_utf8_lossy(&block)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn parse_block(block: &str) -> Option<SseEvent> {
+        let mut event = None;
+        let mut data_lines = Vec::new();
+
+        for line in block.lines() {
+            if let Some(value) = line.strip_prefix("event:") {
+                event = Some(value.trim().to_string());
+            } else if let Some(value) = line.strip_prefix("data:") {
+                data_lines.push(value.strip_prefix(' ').unwrap_or(value));
+            }
+        }
+
+        if event.is_none() && data_lines.is_empty() {
+            return None;
+        }
+
+        Some(SseEvent {
+            event,
+            data: data_lines.join("\n"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_split_across_chunks() {
+        let mut parser = SseParser::default();
+
+        assert!(parser.push(b"event: ping\ndata: {\"a\"").is_empty());
+        let events = parser.push(b":1}\r\n\r\n: comment\n\ndata: two\n\n");
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent { event: Some("ping".to_string()), data: "{\"a\":1}".to_string() },
+                SseEvent { event: None, data: "two".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multibyte_character_split_across_chunks() {
+        let mut parser = SseParser::default();
+        let bytes = "data: café\n\n".as_bytes();
+
+        assert!(parser.push(&bytes[..10]).is_empty());
+        assert_eq!(parser.push(&bytes[10..])[0].data, "café");
+    }
+}
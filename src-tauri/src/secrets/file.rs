@@ -0,0 +1,194 @@
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{SecretName, SecretStore};
+use crate::config::config_dir;
+
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+pub fn secrets_path() -> PathBuf {
+    config_dir().join("secrets.enc")
+}
+
+pub fn secrets_key_path() -> PathBuf {
+    config_dir().join("secrets.key")
+}
+
+/// Fallback store for machines without a usable keyring: a ChaCha20-Poly1305
+/// encrypted JSON map, keyed by a random key file readable only by the user.
+///
+/// The key sits next to the data, so this guards against keys leaking through
+/// backups, dotfile syncing or a stray `grep` rather than against someone who
+/// can already read the user's home directory.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    key_path: PathBuf,
+    // Serialises read-modify-write cycles within the process
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf, key_path: PathBuf) -> Self {
+        EncryptedFileStore { path, key_path, lock: Mutex::new(()) }
+    }
+
+    fn load_key(&self, create: bool) -> Result<Option<ChaCha20Poly1305>, String> {
+        if self.key_path.exists() {
+            let bytes = fs::read(&self.key_path)
+                .map_err(|e| format!("Failed to read {}: {}", self.key_path.display(), e))?;
+            if bytes.len() != KEY_LEN {
+                return Err(format!("Secrets key {} is corrupt", self.key_path.display()));
+            }
+            return Ok(Some(ChaCha20Poly1305::new(Key::from_slice(&bytes))));
+        }
+
+        if !create {
+            return Ok(None);
+        }
+
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        write_private(&self.key_path, key.as_slice())?;
+        Ok(Some(ChaCha20Poly1305::new(&key)))
+    }
+
+    fn read_all(&self, cipher: &ChaCha20Poly1305) -> Result<BTreeMap<String, String>, String> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let data = fs::read(&self.path).map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?;
+        if data.len() < NONCE_LEN {
+            return Err(format!("Secrets file {} is corrupt", self.path.display()));
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| format!("Failed to decrypt {}", self.path.display()))?;
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Secrets file {} is corrupt: {}", self.path.display(), e))
+    }
+
+    fn write_all(&self, cipher: &ChaCha20Poly1305, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(secrets).map_err(|e| e.to_string())?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt secrets".to_string())?;
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        write_private(&self.path, &data)
+    }
+
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<(), String> {
+        let _guard = self.lock.lock().map_err(|_| "Secrets file lock is poisoned".to_string())?;
+        let cipher = self.load_key(true)?.ok_or("Failed to create secrets key")?;
+        let mut secrets = self.read_all(&cipher)?;
+        change(&mut secrets);
+        self.write_all(&cipher, &secrets)
+    }
+}
+
+impl SecretStore for EncryptedFileStore {
+    fn name(&self) -> &str {
+        "encrypted secrets file"
+    }
+
+    fn get(&self, name: SecretName) -> Result<Option<String>, String> {
+        let _guard = self.lock.lock().map_err(|_| "Secrets file lock is poisoned".to_string())?;
+        let Some(cipher) = self.load_key(false)? else {
+            return Ok(None);
+        };
+        Ok(self.read_all(&cipher)?.remove(name.as_str()))
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> Result<(), String> {
+        self.update(|secrets| {
+            secrets.insert(name.as_str().to_string(), value.to_string());
+        })
+    }
+
+    fn delete(&self, name: SecretName) -> Result<(), String> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        self.update(|secrets| {
+            secrets.remove(name.as_str());
+        })
+    }
+}
+
+/// Writes a 0600 file via a temporary file and rename, like `Config::save_to`.
+fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, data).map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temp_path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {}", temp_path.display(), e))?;
+    }
+
+    fs::rename(&temp_path, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(temp_dir: &TempDir) -> EncryptedFileStore {
+        EncryptedFileStore::new(temp_dir.path().join("secrets.enc"), temp_dir.path().join("secrets.key"))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+
+        assert_eq!(store.get(SecretName::TranscriptionApiKey).unwrap(), None);
+        store.set(SecretName::TranscriptionApiKey, "gsk-secret").unwrap();
+        store.set(SecretName::RefinementApiKey, "sk-ant-secret").unwrap();
+
+        assert_eq!(store.get(SecretName::TranscriptionApiKey).unwrap().as_deref(), Some("gsk-secret"));
+        store.delete(SecretName::TranscriptionApiKey).unwrap();
+        assert_eq!(store.get(SecretName::TranscriptionApiKey).unwrap(), None);
+        assert_eq!(store.get(SecretName::RefinementApiKey).unwrap().as_deref(), Some("sk-ant-secret"));
+    }
+
+    #[test]
+    fn test_file_does_not_contain_plaintext() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = store(&temp_dir);
+        store.set(SecretName::RefinementApiKey, "sk-ant-secret").unwrap();
+
+        let data = fs::read(temp_dir.path().join("secrets.enc")).unwrap();
+        assert!(!String::from_utf8_lossy(&data).contains("sk-ant-secret"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(temp_dir.path().join("secrets.key")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_wrong_key_fails_to_decrypt() {
+        let temp_dir = TempDir::new().unwrap();
+        store(&temp_dir).set(SecretName::RefinementApiKey, "sk-ant-secret").unwrap();
+        fs::write(temp_dir.path().join("secrets.key"), [0u8; KEY_LEN]).unwrap();
+
+        assert!(store(&temp_dir).get(SecretName::RefinementApiKey).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+pub mod file;
+pub mod os_keyring;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub use file::*;
+pub use os_keyring::*;
+
+use crate::config::Config;
+
+/// API keys Vocal knows how to store. The frontend only ever refers to them
+/// by name; values flow from Settings into the store and never back out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretName {
+    /// Key for the configured transcription provider (Groq, OpenAI, Deepgram...).
+    TranscriptionApiKey,
+    /// Key for the configured refinement provider (Anthropic, OpenAI-compatible...).
+    RefinementApiKey,
+}
+
+impl SecretName {
+    pub const ALL: [SecretName; 2] = [SecretName::TranscriptionApiKey, SecretName::RefinementApiKey];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretName::TranscriptionApiKey => "transcription-api-key",
+            SecretName::RefinementApiKey => "refinement-api-key",
+        }
+    }
+
+    /// Field the key used to live under in `config.toml`.
+    fn legacy_config_key(&self) -> &'static str {
+        match self {
+            SecretName::TranscriptionApiKey => "groqApiKey",
+            SecretName::RefinementApiKey => "anthropicApiKey",
+        }
+    }
+}
+
+pub trait SecretStore: Send + Sync {
+    /// Human readable backend name used in logs.
+    fn name(&self) -> &str;
+
+    fn get(&self, name: SecretName) -> Result<Option<String>, String>;
+
+    fn set(&self, name: SecretName, value: &str) -> Result<(), String>;
+
+    /// Removing a secret that is not stored is not an error.
+    fn delete(&self, name: SecretName) -> Result<(), String>;
+}
+
+/// Which keys are set, without revealing their values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretStatus {
+    pub transcription_api_key: bool,
+    pub refinement_api_key: bool,
+}
+
+/// Prefers the OS keyring and falls back to the encrypted file when the
+/// keyring is unavailable (no Secret Service daemon, locked keychain...).
+pub struct Secrets {
+    primary: Box<dyn SecretStore>,
+    fallback: Box<dyn SecretStore>,
+}
+
+impl Secrets {
+    pub fn new(primary: Box<dyn SecretStore>, fallback: Box<dyn SecretStore>) -> Self {
+        Secrets { primary, fallback }
+    }
+
+    /// Keyring entries under the `vocal` service, with `~/.vocal/secrets.enc`
+    /// as the fallback.
+    pub fn open() -> Self {
+        Self::new(
+            Box::new(KeyringStore::new(KEYRING_SERVICE)),
+            Box::new(EncryptedFileStore::new(secrets_path(), secrets_key_path())),
+        )
+    }
+
+    /// Looks a key up, treating a missing key as an empty one so keyless
+    /// providers (Ollama, local Whisper) keep working.
+    pub fn api_key(&self, name: SecretName) -> Result<String, String> {
+        Ok(self.get(name)?.unwrap_or_default())
+    }
+
+    pub fn status(&self) -> Result<SecretStatus, String> {
+        Ok(SecretStatus {
+            transcription_api_key: self.get(SecretName::TranscriptionApiKey)?.is_some(),
+            refinement_api_key: self.get(SecretName::RefinementApiKey)?.is_some(),
+        })
+    }
+
+    /// Moves API keys left in `config.toml` by earlier versions into the store
+    /// and rewrites the file without them. Returns how many keys were moved.
+    pub fn migrate_legacy_config(&self, config_path: &Path) -> Result<usize, String> {
+        if !config_path.exists() {
+            return Ok(0);
+        }
+
+        let content = std::fs::read_to_string(config_path)
+            .map_err(|e| format!("Failed to read {}: {}", config_path.display(), e))?;
+        let table: toml::Table = match toml::from_str(&content) {
+            Ok(table) => table,
+            // Config::load reports invalid files; nothing to migrate from here
+            Err(_) => return Ok(0),
+        };
+
+        let legacy: Vec<(SecretName, Option<&str>)> = SecretName::ALL
+            .iter()
+            .filter_map(|name| table.get(name.legacy_config_key()).map(|value| (*name, value.as_str())))
+            .collect();
+        if legacy.is_empty() {
+            return Ok(0);
+        }
+
+        let mut migrated = 0;
+        for (name, value) in &legacy {
+            if let Some(value) = value.filter(|value| !value.trim().is_empty()) {
+                self.set(*name, value)?;
+                migrated += 1;
+            }
+        }
+
+        // Config does not know the old fields, so a load/save round trip drops them
+        Config::load_from(config_path)
+            .and_then(|config| config.save_to(config_path))
+            .map_err(|e| format!("Failed to rewrite {}: {}", config_path.display(), e))?;
+
+        Ok(migrated)
+    }
+}
+
+impl SecretStore for Secrets {
+    fn name(&self) -> &str {
+        "secrets"
+    }
+
+    fn get(&self, name: SecretName) -> Result<Option<String>, String> {
+        match self.primary.get(name) {
+            Ok(Some(value)) => return Ok(Some(value)),
+            Ok(None) => {}
+            Err(e) => println!("{} unavailable, using {}: {}", self.primary.name(), self.fallback.name(), e),
+        }
+        self.fallback.get(name)
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> Result<(), String> {
+        match self.primary.set(name, value) {
+            Ok(()) => {
+                // Don't leave an older copy behind in the fallback
+                if let Err(e) = self.fallback.delete(name) {
+                    println!("Failed to clear {} from {}: {}", name.as_str(), self.fallback.name(), e);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                println!("{} unavailable, using {}: {}", self.primary.name(), self.fallback.name(), e);
+                self.fallback.set(name, value)
+            }
+        }
+    }
+
+    fn delete(&self, name: SecretName) -> Result<(), String> {
+        let fallback = self.fallback.delete(name);
+        if let Err(e) = self.primary.delete(name) {
+            match self.primary.get(name) {
+                // The key is still there to be read, so the delete really failed
+                Ok(Some(_)) => return Err(e),
+                // Not stored, or the keyring is unreachable and can't hand it out either
+                Ok(None) | Err(_) => println!("Failed to delete {} from {}: {}", name.as_str(), self.primary.name(), e),
+            }
+        }
+        fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        values: Mutex<HashMap<SecretName, String>>,
+        unavailable: bool,
+        /// Reads work, deletes fail
+        read_only: bool,
+    }
+
+    impl MemoryStore {
+        fn unavailable() -> Self {
+            MemoryStore { unavailable: true, ..Default::default() }
+        }
+    }
+
+    impl SecretStore for MemoryStore {
+        fn name(&self) -> &str {
+            "memory"
+        }
+
+        fn get(&self, name: SecretName) -> Result<Option<String>, String> {
+            if self.unavailable {
+                return Err("no keyring".to_string());
+            }
+            Ok(self.values.lock().unwrap().get(&name).cloned())
+        }
+
+        fn set(&self, name: SecretName, value: &str) -> Result<(), String> {
+            if self.unavailable {
+                return Err("no keyring".to_string());
+            }
+            self.values.lock().unwrap().insert(name, value.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: SecretName) -> Result<(), String> {
+            if self.unavailable {
+                return Err("no keyring".to_string());
+            }
+            if self.read_only {
+                return Err("access denied".to_string());
+            }
+            self.values.lock().unwrap().remove(&name);
+            Ok(())
+        }
+    }
+
+    fn file_store(temp_dir: &TempDir) -> Box<dyn SecretStore> {
+        Box::new(EncryptedFileStore::new(
+            temp_dir.path().join("secrets.enc"),
+            temp_dir.path().join("secrets.key"),
+        ))
+    }
+
+    #[test]
+    fn test_falls_back_when_keyring_is_unavailable() {
+        let temp_dir = TempDir::new().unwrap();
+        let secrets = Secrets::new(Box::new(MemoryStore::unavailable()), file_store(&temp_dir));
+
+        secrets.set(SecretName::RefinementApiKey, "sk-ant").unwrap();
+
+        assert_eq!(secrets.api_key(SecretName::RefinementApiKey).unwrap(), "sk-ant");
+        assert_eq!(secrets.api_key(SecretName::TranscriptionApiKey).unwrap(), "");
+        assert_eq!(
+            secrets.status().unwrap(),
+            SecretStatus { transcription_api_key: false, refinement_api_key: true }
+        );
+
+        secrets.delete(SecretName::RefinementApiKey).unwrap();
+        assert_eq!(secrets.get(SecretName::RefinementApiKey).unwrap(), None);
+    }
+
+    #[test]
+    fn test_failed_keyring_delete_is_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let keyring = MemoryStore { read_only: true, ..Default::default() };
+        keyring.values.lock().unwrap().insert(SecretName::RefinementApiKey, "sk-ant".to_string());
+        let secrets = Secrets::new(Box::new(keyring), file_store(&temp_dir));
+
+        assert_eq!(secrets.delete(SecretName::RefinementApiKey).unwrap_err(), "access denied");
+        // Nothing stored is nothing to delete
+        secrets.delete(SecretName::TranscriptionApiKey).unwrap();
+    }
+
+    #[test]
+    fn test_keyring_write_clears_fallback_copy() {
+        let temp_dir = TempDir::new().unwrap();
+        file_store(&temp_dir).set(SecretName::TranscriptionApiKey, "old").unwrap();
+
+        let secrets = Secrets::new(Box::new(MemoryStore::default()), file_store(&temp_dir));
+        assert_eq!(secrets.api_key(SecretName::TranscriptionApiKey).unwrap(), "old");
+
+        secrets.set(SecretName::TranscriptionApiKey, "new").unwrap();
+        assert_eq!(secrets.api_key(SecretName::TranscriptionApiKey).unwrap(), "new");
+        assert_eq!(file_store(&temp_dir).get(SecretName::TranscriptionApiKey).unwrap(), None);
+    }
+
+    #[test]
+    fn test_migrates_keys_out_of_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "groqApiKey = \"gsk-123\"\nanthropicApiKey = \"\"\nhotkey = \"Alt+V\"\n",
+        )
+        .unwrap();
+
+        let secrets = Secrets::new(Box::new(MemoryStore::default()), file_store(&temp_dir));
+        assert_eq!(secrets.migrate_legacy_config(&config_path).unwrap(), 1);
+
+        assert_eq!(secrets.api_key(SecretName::TranscriptionApiKey).unwrap(), "gsk-123");
+        assert_eq!(secrets.get(SecretName::RefinementApiKey).unwrap(), None);
+
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(!content.contains("ApiKey"));
+        assert_eq!(Config::load_from(&config_path).unwrap().hotkey, "Alt+V");
+
+        assert_eq!(secrets.migrate_legacy_config(&config_path).unwrap(), 0);
+    }
+}
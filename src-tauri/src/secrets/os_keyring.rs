@@ -0,0 +1,47 @@
+use super::{SecretName, SecretStore};
+
+/// Service name the keyring entries are filed under.
+pub const KEYRING_SERVICE: &str = "vocal";
+
+/// Secret Service on Linux, Keychain on macOS, Credential Manager on Windows.
+pub struct KeyringStore {
+    service: String,
+}
+
+impl KeyringStore {
+    pub fn new(service: &str) -> Self {
+        KeyringStore { service: service.to_string() }
+    }
+
+    fn entry(&self, name: SecretName) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(&self.service, name.as_str())
+            .map_err(|e| format!("Failed to open keyring entry {}: {}", name.as_str(), e))
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn name(&self) -> &str {
+        "OS keyring"
+    }
+
+    fn get(&self, name: SecretName) -> Result<Option<String>, String> {
+        match self.entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Failed to read {} from keyring: {}", name.as_str(), e)),
+        }
+    }
+
+    fn set(&self, name: SecretName, value: &str) -> Result<(), String> {
+        self.entry(name)?
+            .set_password(value)
+            .map_err(|e| format!("Failed to write {} to keyring: {}", name.as_str(), e))
+    }
+
+    fn delete(&self, name: SecretName) -> Result<(), String> {
+        match self.entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete {} from keyring: {}", name.as_str(), e)),
+        }
+    }
+}
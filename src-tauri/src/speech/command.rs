@@ -0,0 +1,72 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::Speaker;
+
+/// A local TTS engine run through the shell, e.g.
+/// `piper --model en_US-amy-medium.onnx --output-raw | aplay -r 22050 -f S16_LE`.
+pub struct CommandSpeaker {
+    command: String,
+}
+
+impl CommandSpeaker {
+    pub fn new(command: &str) -> Self {
+        CommandSpeaker {
+            command: command.to_string(),
+        }
+    }
+}
+
+impl Speaker for CommandSpeaker {
+    fn name(&self) -> &str {
+        "command"
+    }
+
+    fn speak(&self, text: &str) -> Result<(), String> {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(&self.command);
+        speak_through(command, text)
+    }
+}
+
+/// Runs a TTS program with `text` on stdin and waits for it to finish, so the
+/// text can't be mistaken for options.
+pub(crate) fn speak_through(mut command: Command, text: &str) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to send text to {}: {}", program, e))?;
+    }
+
+    let status = child.wait().map_err(|e| format!("{} failed: {}", program, e))?;
+    if !status.success() {
+        return Err(format!("{} exited with {}", program, status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_command_reads_text_from_stdin() {
+        let temp_dir = TempDir::new().unwrap();
+        let out = temp_dir.path().join("spoken.txt");
+        let speaker = CommandSpeaker::new(&format!("cat > '{}'", out.display()));
+
+        speaker.speak("-v is not an option here").unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "-v is not an option here");
+
+        let error = CommandSpeaker::new("exit 3").speak("hello").unwrap_err();
+        assert!(error.contains("exited with"));
+    }
+}
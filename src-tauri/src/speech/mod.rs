@@ -0,0 +1,164 @@
+pub mod command;
+pub mod system;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+pub use command::*;
+pub use system::*;
+
+/// Instructions for condensing Claude's response before it is read out.
+pub const SUMMARY_PROMPT: &str = "Summarize the following response from a coding assistant in at most two short \
+sentences, to be read aloud. Say what was done and whether anything needs the user's attention. \
+Don't use markdown, code or file paths unless essential.";
+
+pub trait Speaker: Send + Sync {
+    /// Human readable backend name used in logs.
+    fn name(&self) -> &str;
+
+    /// Speaks `text` and returns once it has been read out.
+    fn speak(&self, text: &str) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeechProvider {
+    /// `say` on macOS, `espeak` elsewhere
+    #[default]
+    #[serde(rename = "system")]
+    System,
+    #[serde(rename = "say")]
+    Say,
+    #[serde(rename = "espeak")]
+    Espeak,
+    /// Any local engine behind a shell command, e.g. Piper
+    #[serde(rename = "command")]
+    Command,
+}
+
+/// Selects the text-to-speech backend and what gets read out.
+///
+/// `voice` and `rate` (words per minute) fall back to the engine defaults.
+/// The `command` provider runs `command` through `sh -c` with the text on stdin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeechConfig {
+    pub provider: SpeechProvider,
+    pub voice: Option<String>,
+    pub rate: Option<u32>,
+    pub command: Option<String>,
+    /// Read Claude's final response out when a hands-free cycle ends
+    pub readback: bool,
+    /// Condense the response through the refinement backend first
+    pub summarize: bool,
+    /// Longer responses are cut at a sentence boundary
+    pub max_chars: usize,
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        SpeechConfig {
+            provider: SpeechProvider::default(),
+            voice: None,
+            rate: None,
+            command: None,
+            readback: true,
+            summarize: false,
+            max_chars: 600,
+        }
+    }
+}
+
+impl SpeechConfig {
+    pub fn build(&self) -> Result<Box<dyn Speaker>, String> {
+        let voice = self.voice.clone().filter(|voice| !voice.trim().is_empty());
+
+        let speaker: Box<dyn Speaker> = match self.provider {
+            SpeechProvider::System if cfg!(target_os = "macos") => Box::new(SaySpeaker::new(voice, self.rate)),
+            SpeechProvider::System => Box::new(EspeakSpeaker::new(voice, self.rate)),
+            SpeechProvider::Say => Box::new(SaySpeaker::new(voice, self.rate)),
+            SpeechProvider::Espeak => Box::new(EspeakSpeaker::new(voice, self.rate)),
+            SpeechProvider::Command => {
+                let command = self
+                    .command
+                    .as_deref()
+                    .filter(|command| !command.trim().is_empty())
+                    .ok_or("A command is required for the command speech provider")?;
+                Box::new(CommandSpeaker::new(command))
+            }
+        };
+
+        Ok(speaker)
+    }
+}
+
+/// Turns a markdown response into something worth listening to: code blocks
+/// are skipped, formatting and link targets dropped, and anything past
+/// `max_chars` cut at the last full sentence.
+pub fn prepare_readback(text: &str, max_chars: usize) -> String {
+    let code_block = Regex::new(r"(?s)```.*?(```|$)").unwrap();
+    let link = Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap();
+    let markup = Regex::new(r"(?m)^\s*(#+|[-*+]|>)\s+|\*\*|__|`").unwrap();
+    let whitespace = Regex::new(r"\s+").unwrap();
+
+    let text = code_block.replace_all(text, " (code omitted) ");
+    let text = link.replace_all(&text, "$1");
+    let text = markup.replace_all(&text, "");
+    let text = whitespace.replace_all(&text, " ");
+    let text = text.trim();
+
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    match cut.rfind(['.', '!', '?']) {
+        Some(end) if end > 0 => cut[..=end].to_string(),
+        _ => match cut.rfind(' ') {
+            Some(end) => format!("{}…", &cut[..end]),
+            None => format!("{}…", cut),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_default_config_uses_system_voice() {
+        let config = SpeechConfig::default();
+        assert!(config.readback);
+        let expected = if cfg!(target_os = "macos") { "say" } else { "espeak" };
+        assert_eq!(config.build().unwrap().name(), expected);
+    }
+
+    #[test]
+    fn test_config_deserialization() {
+        let config: SpeechConfig = serde_json::from_value(json!({
+            "provider": "command",
+            "command": "piper --model en_US-amy-medium.onnx --output-raw | aplay -r 22050 -f S16_LE",
+            "summarize": true
+        }))
+        .unwrap();
+        assert_eq!(config.provider, SpeechProvider::Command);
+        assert!(config.summarize);
+        assert_eq!(config.max_chars, 600);
+        assert_eq!(config.build().unwrap().name(), "command");
+
+        let missing_command = SpeechConfig { provider: SpeechProvider::Command, ..Default::default() };
+        assert!(missing_command.build().is_err());
+    }
+
+    #[test]
+    fn test_prepare_readback() {
+        let text = "## Done\n\nI fixed **two** bugs in `parser.rs`:\n\n- the [tokenizer](src/lexer.rs)\n- the cache\n\n```rust\nfn main() {}\n```\nAll tests pass.";
+        assert_eq!(
+            prepare_readback(text, 600),
+            "Done I fixed two bugs in parser.rs: the tokenizer the cache (code omitted) All tests pass."
+        );
+
+        let long = "First sentence. Second sentence is much longer than the rest.";
+        assert_eq!(prepare_readback(long, 30), "First sentence.");
+        assert_eq!(prepare_readback("no sentence end here at all", 12), "no sentence…");
+    }
+}
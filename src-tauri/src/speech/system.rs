@@ -0,0 +1,63 @@
+use std::process::Command;
+
+use super::{speak_through, Speaker};
+
+/// macOS `say`.
+pub struct SaySpeaker {
+    voice: Option<String>,
+    rate: Option<u32>,
+}
+
+impl SaySpeaker {
+    pub fn new(voice: Option<String>, rate: Option<u32>) -> Self {
+        SaySpeaker { voice, rate }
+    }
+}
+
+impl Speaker for SaySpeaker {
+    fn name(&self) -> &str {
+        "say"
+    }
+
+    fn speak(&self, text: &str) -> Result<(), String> {
+        let mut command = Command::new("say");
+        if let Some(voice) = &self.voice {
+            command.arg("-v").arg(voice);
+        }
+        if let Some(rate) = self.rate {
+            command.arg("-r").arg(rate.to_string());
+        }
+        command.arg("-f").arg("-");
+        speak_through(command, text)
+    }
+}
+
+/// `espeak` or `espeak-ng`, which installs an `espeak` alias.
+pub struct EspeakSpeaker {
+    voice: Option<String>,
+    rate: Option<u32>,
+}
+
+impl EspeakSpeaker {
+    pub fn new(voice: Option<String>, rate: Option<u32>) -> Self {
+        EspeakSpeaker { voice, rate }
+    }
+}
+
+impl Speaker for EspeakSpeaker {
+    fn name(&self) -> &str {
+        "espeak"
+    }
+
+    fn speak(&self, text: &str) -> Result<(), String> {
+        let mut command = Command::new("espeak");
+        if let Some(voice) = &self.voice {
+            command.arg("-v").arg(voice);
+        }
+        if let Some(rate) = self.rate {
+            command.arg("-s").arg(rate.to_string());
+        }
+        command.arg("--stdin");
+        speak_through(command, text)
+    }
+}
@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{AudioClip, Transcriber};
+
+pub const DEEPGRAM_BASE_URL: &str = "https://api.deepgram.com/v1";
+pub const DEEPGRAM_DEFAULT_MODEL: &str = "nova-2";
+
+/// Deepgram-style `/listen` API: raw audio body, `Token` authorization and
+/// the transcript nested under `results.channels[].alternatives[]`.
+pub struct DeepgramTranscriber {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl DeepgramTranscriber {
+    pub fn new(base_url: &str, model: &str, api_key: &str) -> Self {
+        DeepgramTranscriber {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transcriber for DeepgramTranscriber {
+    fn name(&self) -> &str {
+        "Deepgram"
+    }
+
+    async fn transcribe(&self, audio: AudioClip) -> Result<String, String> {
+        let response = self
+            .client
+            .post(format!("{}/listen", self.base_url))
+            .query(&[("model", self.model.as_str()), ("smart_format", "true")])
+            .header("Authorization", format!("Token {}", self.api_key))
+            .header("Content-Type", audio.mime_type)
+            .body(audio.data)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
+            return Err(format!("Transcription failed ({}): {}", status, error_body));
+        }
+
+        let response_json: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        response_json["results"]["channels"][0]["alternatives"][0]["transcript"]
+            .as_str()
+            .map(|text| text.to_string())
+            .ok_or_else(|| "No transcript found in response".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_transcribe_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/listen")
+            .match_query(Matcher::UrlEncoded("model".to_string(), "nova-2".to_string()))
+            .match_header("authorization", "Token dg-key")
+            .match_header("content-type", "audio/webm")
+            .match_body(vec![7u8, 8, 9])
+            .with_status(200)
+            .with_body(r#"{"results":{"channels":[{"alternatives":[{"transcript":"run the tests"}]}]}}"#)
+            .create_async()
+            .await;
+
+        let transcriber = DeepgramTranscriber::new(&server.url(), DEEPGRAM_DEFAULT_MODEL, "dg-key");
+        let text = transcriber.transcribe(AudioClip::webm(vec![7, 8, 9])).await.unwrap();
+
+        assert_eq!(text, "run the tests");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_missing_transcript_is_an_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/listen")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{"results":{"channels":[]}}"#)
+            .create_async()
+            .await;
+
+        let transcriber = DeepgramTranscriber::new(&server.url(), DEEPGRAM_DEFAULT_MODEL, "dg-key");
+        assert!(transcriber.transcribe(AudioClip::webm(vec![0])).await.is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use super::{AudioClip, Transcriber};
+
+/// whisper.cpp only accepts 16 kHz mono f32 samples.
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+// Loading a model takes seconds, so keep the last one around between dictations
+static LOADED_MODEL: Mutex<Option<(PathBuf, Arc<WhisperContext>)>> = Mutex::new(None);
+
+/// CPU-only whisper.cpp engine; audio never leaves the machine.
+pub struct LocalWhisperTranscriber {
+    model_path: PathBuf,
+}
+
+impl LocalWhisperTranscriber {
+    pub fn new(model_path: PathBuf) -> Self {
+        LocalWhisperTranscriber { model_path }
+    }
+}
+
+#[async_trait]
+impl Transcriber for LocalWhisperTranscriber {
+    fn name(&self) -> &str {
+        "local Whisper"
+    }
+
+    async fn transcribe(&self, audio: AudioClip) -> Result<String, String> {
+        let model_path = self.model_path.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let samples = decode_wav(&audio.data)?;
+            let context = load_model(&model_path)?;
+            run_whisper(&context, &samples)
+        })
+        .await
+        .map_err(|e| format!("Local transcription task failed: {}", e))?
+    }
+}
+
+fn load_model(model_path: &Path) -> Result<Arc<WhisperContext>, String> {
+    let mut loaded = LOADED_MODEL
+        .lock()
+        .map_err(|_| "Local Whisper model cache is poisoned".to_string())?;
+
+    if let Some((path, context)) = loaded.as_ref() {
+        if path == model_path {
+            return Ok(context.clone());
+        }
+    }
+
+    println!("Loading local Whisper model from {}", model_path.display());
+    let mut params = WhisperContextParameters::default();
+    params.use_gpu(false);
+
+    let path = model_path
+        .to_str()
+        .ok_or_else(|| format!("Model path is not valid UTF-8: {}", model_path.display()))?;
+    let context = WhisperContext::new_with_params(path, params)
+        .map_err(|e| format!("Failed to load Whisper model {}: {}", model_path.display(), e))?;
+
+    let context = Arc::new(context);
+    *loaded = Some((model_path.to_path_buf(), context.clone()));
+    Ok(context)
+}
+
+fn run_whisper(context: &WhisperContext, samples: &[f32]) -> Result<String, String> {
+    let mut state = context
+        .create_state()
+        .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get().min(8) as i32)
+        .unwrap_or(4);
+
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_n_threads(threads);
+    params.set_translate(false);
+    params.set_no_context(true);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+
+    state
+        .full(params, samples)
+        .map_err(|e| format!("Local transcription failed: {}", e))?;
+
+    let segments = state
+        .full_n_segments()
+        .map_err(|e| format!("Failed to read transcription segments: {}", e))?;
+
+    let mut text = String::new();
+    for segment in 0..segments {
+        let segment_text = state
+            .full_get_segment_text_lossy(segment)
+            .map_err(|e| format!("Failed to read transcription segment {}: {}", segment, e))?;
+        text.push_str(&segment_text);
+    }
+
+    Ok(text.trim().to_string())
+}
+
+/// Decodes a PCM WAV file into 16 kHz mono samples, downmixing and
+/// resampling as needed.
+pub fn decode_wav(data: &[u8]) -> Result<Vec<f32>, String> {
+    let mut reader = hound::WavReader::new(Cursor::new(data))
+        .map_err(|e| format!("Local transcription expects WAV audio: {}", e))?;
+    let spec = reader.spec();
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read WAV samples: {}", e))?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Failed to read WAV samples: {}", e))?
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok(resample(&mono, spec.sample_rate, WHISPER_SAMPLE_RATE))
+}
+
+fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = (samples.len() as f64 / ratio).floor() as usize;
+
+    (0..output_len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = samples[index];
+            let next = samples.get(index + 1).copied().unwrap_or(current);
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(spec: hound::WavSpec, samples: &[i16]) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut buffer, spec).unwrap();
+        for sample in samples {
+            writer.write_sample(*sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        buffer.into_inner()
+    }
+
+    #[test]
+    fn test_decode_wav_downmixes_and_resamples() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 32_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let samples: Vec<i16> = std::iter::repeat([i16::MAX / 2, 0]).take(3200).flatten().collect();
+
+        let decoded = decode_wav(&wav_bytes(spec, &samples)).unwrap();
+
+        assert_eq!(decoded.len(), 1600);
+        assert!(decoded.iter().all(|s| (s - 0.25).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_decode_wav_rejects_other_formats() {
+        assert!(decode_wav(b"\x1a\x45\xdf\xa3 webm").is_err());
+    }
+}
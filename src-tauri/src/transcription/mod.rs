@@ -0,0 +1,190 @@
+pub mod deepgram;
+#[cfg(feature = "local-whisper")]
+pub mod local;
+pub mod models;
+pub mod openai;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+pub use deepgram::*;
+#[cfg(feature = "local-whisper")]
+pub use local::*;
+pub use openai::*;
+
+/// Recorded audio handed to a transcription backend.
+#[derive(Debug, Clone)]
+pub struct AudioClip {
+    pub data: Vec<u8>,
+    pub file_name: String,
+    pub mime_type: String,
+}
+
+impl AudioClip {
+    pub fn new(data: Vec<u8>, mime_type: &str) -> Self {
+        let extension = mime_type
+            .split(';')
+            .next()
+            .and_then(|essence| essence.split('/').nth(1))
+            .unwrap_or("webm");
+
+        AudioClip {
+            data,
+            file_name: format!("audio.{}", extension),
+            mime_type: mime_type.to_string(),
+        }
+    }
+
+    /// The dictation window records with `MediaRecorder`, which produces WebM.
+    pub fn webm(data: Vec<u8>) -> Self {
+        Self::new(data, "audio/webm")
+    }
+}
+
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    /// Human readable backend name used in logs.
+    fn name(&self) -> &str;
+
+    async fn transcribe(&self, audio: AudioClip) -> Result<String, String>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TranscriptionProvider {
+    #[default]
+    #[serde(rename = "groq")]
+    Groq,
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "deepgram")]
+    Deepgram,
+    #[serde(rename = "openai-compatible")]
+    OpenAiCompatible,
+    /// Offline whisper.cpp engine, available with the `local-whisper` feature.
+    #[serde(rename = "local")]
+    Local,
+}
+
+/// Selects and parameterises the transcription backend.
+///
+/// `base_url` and `model` fall back to the provider defaults when unset. For the
+/// local provider `model` names a downloaded GGML model and `model_path`
+/// points at a model file directly.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranscriptionConfig {
+    pub provider: TranscriptionProvider,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    pub model_path: Option<String>,
+}
+
+impl TranscriptionConfig {
+    pub fn build(&self, api_key: &str) -> Result<Box<dyn Transcriber>, String> {
+        let base_url = self.base_url.as_deref().filter(|url| !url.trim().is_empty());
+        let model = self.model.as_deref().filter(|model| !model.trim().is_empty());
+
+        let transcriber: Box<dyn Transcriber> = match self.provider {
+            TranscriptionProvider::Groq => Box::new(OpenAiTranscriber::new(
+                "Groq",
+                base_url.unwrap_or(GROQ_BASE_URL),
+                model.unwrap_or(GROQ_DEFAULT_MODEL),
+                api_key,
+            )),
+            TranscriptionProvider::OpenAi => Box::new(OpenAiTranscriber::new(
+                "OpenAI",
+                base_url.unwrap_or(OPENAI_BASE_URL),
+                model.unwrap_or(OPENAI_DEFAULT_MODEL),
+                api_key,
+            )),
+            TranscriptionProvider::Deepgram => Box::new(DeepgramTranscriber::new(
+                base_url.unwrap_or(DEEPGRAM_BASE_URL),
+                model.unwrap_or(DEEPGRAM_DEFAULT_MODEL),
+                api_key,
+            )),
+            TranscriptionProvider::OpenAiCompatible => {
+                let base_url = base_url
+                    .ok_or("A base URL is required for the OpenAI-compatible transcription provider")?;
+                Box::new(OpenAiTranscriber::new(
+                    "OpenAI-compatible server",
+                    base_url,
+                    model.unwrap_or(OPENAI_DEFAULT_MODEL),
+                    api_key,
+                ))
+            }
+            TranscriptionProvider::Local => self.build_local()?,
+        };
+
+        Ok(transcriber)
+    }
+
+    #[cfg(feature = "local-whisper")]
+    fn build_local(&self) -> Result<Box<dyn Transcriber>, String> {
+        let model_path = models::resolve_model_path(
+            &models::models_dir(),
+            self.model_path.as_deref(),
+            self.model.as_deref(),
+        )?;
+        Ok(Box::new(LocalWhisperTranscriber::new(model_path)))
+    }
+
+    #[cfg(not(feature = "local-whisper"))]
+    fn build_local(&self) -> Result<Box<dyn Transcriber>, String> {
+        Err("Local transcription is not available in this build. Rebuild Vocal with `--features local-whisper`.".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_default_config_uses_groq() {
+        let transcriber = TranscriptionConfig::default().build("key").unwrap();
+        assert_eq!(transcriber.name(), "Groq");
+    }
+
+    #[test]
+    fn test_config_deserialization() {
+        let config: TranscriptionConfig = serde_json::from_value(json!({
+            "provider": "openai-compatible",
+            "baseUrl": "http://localhost:9000/v1",
+            "model": "large-v3"
+        }))
+        .unwrap();
+
+        assert_eq!(config.provider, TranscriptionProvider::OpenAiCompatible);
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:9000/v1"));
+
+        let empty: TranscriptionConfig = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty, TranscriptionConfig::default());
+    }
+
+    #[test]
+    fn test_openai_compatible_requires_base_url() {
+        let config = TranscriptionConfig {
+            provider: TranscriptionProvider::OpenAiCompatible,
+            base_url: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert!(config.build("").is_err());
+    }
+
+    #[cfg(not(feature = "local-whisper"))]
+    #[test]
+    fn test_local_provider_requires_feature() {
+        let config = TranscriptionConfig {
+            provider: TranscriptionProvider::Local,
+            ..Default::default()
+        };
+        let error = config.build("").err().unwrap();
+        assert!(error.contains("local-whisper"));
+    }
+
+    #[test]
+    fn test_audio_clip_file_name_from_mime_type() {
+        assert_eq!(AudioClip::webm(vec![]).file_name, "audio.webm");
+        assert_eq!(AudioClip::new(vec![], "audio/ogg;codecs=opus").file_name, "audio.ogg");
+    }
+}
@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+pub const WHISPER_MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+pub const DEFAULT_LOCAL_MODEL: &str = "base.en";
+
+pub fn models_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".vocal")
+        .join("models")
+}
+
+/// whisper.cpp publishes its GGML models as `ggml-<name>.bin`.
+pub fn model_file_name(model: &str) -> String {
+    format!("ggml-{}.bin", model)
+}
+
+/// Model names end up in a file name under the models directory, so they
+/// can't name a path.
+pub fn validate_model_name(model: &str) -> Result<(), String> {
+    if model.is_empty() || model.contains(['/', '\\']) || model.contains("..") {
+        return Err(format!("Invalid Whisper model name '{}'", model));
+    }
+    Ok(())
+}
+
+/// Resolves the model file for the local engine: an explicit `model_path` wins,
+/// otherwise the named model is looked up in `dir`.
+pub fn resolve_model_path(dir: &Path, model_path: Option<&str>, model: Option<&str>) -> Result<PathBuf, String> {
+    if let Some(path) = model_path.filter(|path| !path.trim().is_empty()) {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            return Err(format!("Whisper model not found at {}", path.display()));
+        }
+        return Ok(path);
+    }
+
+    let model = model.filter(|model| !model.trim().is_empty()).unwrap_or(DEFAULT_LOCAL_MODEL);
+    validate_model_name(model)?;
+    let path = dir.join(model_file_name(model));
+    if !path.is_file() {
+        return Err(format!(
+            "Whisper model '{}' is not downloaded (expected {}). Download it from Settings or set a model path.",
+            model,
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// Downloads a GGML model into `dir`, writing to a `.part` file first so an
+/// interrupted download never looks like a usable model.
+pub async fn download_model(base_url: &str, model: &str, dir: &Path) -> Result<PathBuf, String> {
+    validate_model_name(model)?;
+    let file_name = model_file_name(model);
+    let url = format!("{}/{}", base_url.trim_end_matches('/'), file_name);
+
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|e| format!("Failed to create models directory {}: {}", dir.display(), e))?;
+
+    let mut response = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Model download failed ({}): {}", status, url));
+    }
+
+    let destination = dir.join(&file_name);
+    let partial = dir.join(format!("{}.part", file_name));
+    let mut file = tokio::fs::File::create(&partial)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Model download interrupted: {}", e))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+
+    tokio::fs::rename(&partial, &destination)
+        .await
+        .map_err(|e| format!("Failed to move model into place: {}", e))?;
+
+    Ok(destination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_model_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        assert!(resolve_model_path(dir, None, None).is_err());
+
+        std::fs::write(dir.join("ggml-base.en.bin"), b"model").unwrap();
+        assert_eq!(resolve_model_path(dir, None, None).unwrap(), dir.join("ggml-base.en.bin"));
+        assert!(resolve_model_path(dir, None, Some("small")).is_err());
+
+        let custom = dir.join("custom.bin");
+        std::fs::write(&custom, b"model").unwrap();
+        assert_eq!(
+            resolve_model_path(dir, Some(custom.to_str().unwrap()), Some("small")).unwrap(),
+            custom
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_model_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/ggml-tiny.en.bin")
+            .with_status(200)
+            .with_body(vec![42u8; 1024])
+            .create_async()
+            .await;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = download_model(&server.url(), "tiny.en", temp_dir.path()).await.unwrap();
+
+        assert_eq!(path, temp_dir.path().join("ggml-tiny.en.bin"));
+        assert_eq!(std::fs::read(&path).unwrap().len(), 1024);
+        assert!(!temp_dir.path().join("ggml-tiny.en.bin.part").exists());
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_model_names_cannot_leave_the_models_directory() {
+        assert!(validate_model_name("base.en").is_ok());
+        assert!(validate_model_name("large-v3-turbo").is_ok());
+        for name in ["", "../../.ssh/authorized_keys", "sub/model", "..\\model", "a..b"] {
+            assert!(validate_model_name(name).is_err(), "{}", name);
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        assert!(resolve_model_path(temp_dir.path(), None, Some("../base.en")).unwrap_err().contains("Invalid"));
+    }
+
+    #[tokio::test]
+    async fn test_download_rejects_path_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let error = download_model("http://127.0.0.1:9", "../escape", temp_dir.path()).await.unwrap_err();
+        assert_eq!(error, "Invalid Whisper model name '../escape'");
+    }
+
+    #[tokio::test]
+    async fn test_download_missing_model_fails() {
+        let mut server = mockito::Server::new_async().await;
+        server.mock("GET", "/ggml-nope.bin").with_status(404).create_async().await;
+
+        let temp_dir = TempDir::new().unwrap();
+        assert!(download_model(&server.url(), "nope", temp_dir.path()).await.is_err());
+        assert!(!temp_dir.path().join("ggml-nope.bin").exists());
+    }
+}
@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::{AudioClip, Transcriber};
+
+pub const GROQ_BASE_URL: &str = "https://api.groq.com/openai/v1";
+pub const GROQ_DEFAULT_MODEL: &str = "whisper-large-v3";
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+pub const OPENAI_DEFAULT_MODEL: &str = "whisper-1";
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// Any server implementing the OpenAI `/audio/transcriptions` endpoint:
+/// Groq, OpenAI itself, or a self-hosted Whisper server.
+pub struct OpenAiTranscriber {
+    client: reqwest::Client,
+    name: String,
+    base_url: String,
+    model: String,
+    api_key: String,
+}
+
+impl OpenAiTranscriber {
+    pub fn new(name: &str, base_url: &str, model: &str, api_key: &str) -> Self {
+        OpenAiTranscriber {
+            client: reqwest::Client::new(),
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}/audio/transcriptions", self.base_url)
+    }
+}
+
+#[async_trait]
+impl Transcriber for OpenAiTranscriber {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn transcribe(&self, audio: AudioClip) -> Result<String, String> {
+        let part = reqwest::multipart::Part::bytes(audio.data)
+            .file_name(audio.file_name)
+            .mime_str(&audio.mime_type)
+            .map_err(|e| format!("Invalid audio MIME type '{}': {}", audio.mime_type, e))?;
+
+        let form = reqwest::multipart::Form::new()
+            .part("file", part)
+            .text("model", self.model.clone())
+            .text("response_format", "json");
+
+        let mut request = self.client.post(self.endpoint()).multipart(form);
+
+        // Self-hosted servers frequently run without authentication
+        if !self.api_key.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.api_key));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_else(|_| "Unable to read error response".to_string());
+            return Err(format!("Transcription failed ({}): {}", status, error_body));
+        }
+
+        let transcription: TranscriptionResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+        Ok(transcription.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_transcribe_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/audio/transcriptions")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex("whisper-large-v3".to_string()),
+                Matcher::Regex(r#"filename="audio.webm""#.to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"text": "hello world"}"#)
+            .create_async()
+            .await;
+
+        let transcriber = OpenAiTranscriber::new(
+            "Groq",
+            &format!("{}/v1/", server.url()),
+            GROQ_DEFAULT_MODEL,
+            "test-key",
+        );
+        let text = transcriber.transcribe(AudioClip::webm(vec![1, 2, 3])).await.unwrap();
+
+        assert_eq!(text, "hello world");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_without_api_key_omits_authorization() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_header("authorization", Matcher::Missing)
+            .with_status(200)
+            .with_body(r#"{"text": "local"}"#)
+            .create_async()
+            .await;
+
+        let transcriber = OpenAiTranscriber::new("Local", &server.url(), "base.en", "");
+        let text = transcriber.transcribe(AudioClip::webm(vec![0])).await.unwrap();
+
+        assert_eq!(text, "local");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_reports_api_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/audio/transcriptions")
+            .with_status(401)
+            .with_body("invalid api key")
+            .create_async()
+            .await;
+
+        let transcriber = OpenAiTranscriber::new("OpenAI", &server.url(), OPENAI_DEFAULT_MODEL, "bad");
+        let error = transcriber.transcribe(AudioClip::webm(vec![0])).await.unwrap_err();
+
+        assert!(error.contains("401"));
+        assert!(error.contains("invalid api key"));
+    }
+}
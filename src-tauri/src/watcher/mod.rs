@@ -0,0 +1,173 @@
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::history;
+use crate::hooks::state;
+
+/// Payload of the `hands-free-changed` event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HandsFreeChanged {
+    pub active: bool,
+    /// Why hands-free mode switched itself off, e.g. a cycle limit
+    pub reason: Option<String>,
+}
+
+/// The hook state files the app reacts to, and the dictation history the
+/// CLI may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StateFile {
+    CycleTrigger,
+    HandsFreeFlag,
+    EmergencyStop,
+    StopReason,
+    History,
+}
+
+impl StateFile {
+    fn for_path(path: &Path) -> Option<Self> {
+        let candidates = [
+            (state::cycle_trigger_path(), StateFile::CycleTrigger),
+            (state::hands_free_flag_path(), StateFile::HandsFreeFlag),
+            (state::emergency_stop_path(), StateFile::EmergencyStop),
+            (state::hands_free_stop_reason_path(), StateFile::StopReason),
+            (history::history_path(), StateFile::History),
+        ];
+        let name = path.file_name()?;
+        candidates
+            .into_iter()
+            .find(|(candidate, _)| candidate.file_name() == Some(name))
+            .map(|(_, file)| file)
+    }
+}
+
+/// What the frontend was last told, so repeated file events for one change
+/// are reported once.
+#[derive(Debug)]
+struct Reported {
+    hands_free_active: bool,
+    emergency_stop: bool,
+}
+
+/// Keeps the watcher alive for the lifetime of the app.
+struct StateWatcher {
+    _watcher: Mutex<RecommendedWatcher>,
+}
+
+/// Watches the hook state files written by the `vocal hook` commands and
+/// emits `restart-recording`, `hands-free-changed` and `emergency-stop` as
+/// soon as they change.
+pub fn start(app: &AppHandle) -> notify::Result<()> {
+    let reported = Mutex::new(Reported {
+        hands_free_active: state::is_hands_free_active(),
+        emergency_stop: state::is_emergency_stop_active(),
+    });
+
+    let handle = app.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+        Ok(event) => {
+            for file in event.paths.iter().filter_map(|path| StateFile::for_path(path)) {
+                match file {
+                    StateFile::CycleTrigger => handle_cycle_trigger(&handle),
+                    // `vocal history --delete` may have removed a prompt listed in the tray
+                    StateFile::History => crate::refresh_tray_menu(&handle),
+                    _ => handle_hands_free_change(&handle, &reported),
+                }
+            }
+        }
+        Err(e) => eprintln!("State watcher error: {}", e),
+    })?;
+
+    // The state files live directly in the home directory
+    let dir = state_dir();
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    println!("Watching hands-free state in {}", dir.display());
+    // The history may not exist before the first dictation; without it the
+    // tray just isn't refreshed for CLI edits
+    let history_path = history::history_path();
+    if let Some(history_dir) = history_path.parent() {
+        let watched = std::fs::create_dir_all(history_dir)
+            .map_err(notify::Error::from)
+            .and_then(|_| watcher.watch(history_dir, RecursiveMode::NonRecursive));
+        if let Err(e) = watched {
+            eprintln!("Not watching history in {}: {}", history_dir.display(), e);
+        }
+    }
+
+    app.manage(StateWatcher {
+        _watcher: Mutex::new(watcher),
+    });
+    Ok(())
+}
+
+fn state_dir() -> PathBuf {
+    state::hands_free_flag_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+}
+
+/// Turns a pending cycle trigger into `restart-recording`. Besides file
+/// events, the frontend calls it once it listens, so a trigger written while
+/// the app was not running isn't consumed before anyone hears the event.
+pub fn handle_cycle_trigger(app: &AppHandle) {
+    // The file event for our own removal lands here too
+    if !state::cycle_trigger_path().exists() {
+        return;
+    }
+    if let Err(e) = state::clear_cycle_trigger() {
+        eprintln!("Failed to clear cycle trigger: {}", e);
+    }
+    println!("Cycle trigger detected - restarting recording");
+    if let Err(e) = app.emit("restart-recording", ()) {
+        eprintln!("Failed to emit restart-recording event: {}", e);
+    }
+}
+
+fn handle_hands_free_change(app: &AppHandle, reported: &Mutex<Reported>) {
+    let mut reported = reported.lock().unwrap();
+
+    let emergency_stop = state::is_emergency_stop_active();
+    if emergency_stop && !reported.emergency_stop {
+        println!("Emergency stop detected");
+        if let Err(e) = app.emit("emergency-stop", ()) {
+            eprintln!("Failed to emit emergency-stop event: {}", e);
+        }
+    }
+    reported.emergency_stop = emergency_stop;
+
+    let active = state::is_hands_free_active();
+    if active != reported.hands_free_active {
+        let change = HandsFreeChanged {
+            active,
+            reason: if active { None } else { state::hands_free_stop_reason() },
+        };
+        println!("Hands-free mode {}", if active { "activated" } else { "deactivated" });
+        if let Err(e) = app.emit("hands-free-changed", change) {
+            eprintln!("Failed to emit hands-free-changed event: {}", e);
+        }
+        reported.hands_free_active = active;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_files_are_recognized() {
+        assert_eq!(StateFile::for_path(&state::cycle_trigger_path()), Some(StateFile::CycleTrigger));
+        assert_eq!(StateFile::for_path(&state::emergency_stop_path()), Some(StateFile::EmergencyStop));
+        assert_eq!(
+            StateFile::for_path(&state_dir().join(".vocal-hands-free-active")),
+            Some(StateFile::HandsFreeFlag)
+        );
+        assert_eq!(StateFile::for_path(&state_dir().join(".vocal-hands-free-stopped")), Some(StateFile::StopReason));
+        assert_eq!(StateFile::for_path(&history::history_path()), Some(StateFile::History));
+        assert_eq!(StateFile::for_path(&history::history_path().with_extension("jsonl.lock")), None);
+        assert_eq!(StateFile::for_path(&state_dir().join(".bashrc")), None);
+        assert_eq!(StateFile::for_path(Path::new("/")), None);
+    }
+}
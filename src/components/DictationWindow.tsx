@@ -1,4 +1,4 @@
-import { useEffect, useRef } from "react";
+import { useEffect, useRef, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 
@@ -8,7 +8,21 @@ import { useAppState } from "@/hooks/use-app-state";
 import { useConfig } from "@/hooks/use-config";
 import { useRecording } from "@/hooks/use-recording";
 import { apiService } from "@/services/api";
+import { sessionService } from "@/services/sessions";
+import {
+  approvalService,
+  type ApprovalDecision,
+  type ApprovalRequest,
+  type ApprovalResolved,
+} from "@/services/approvals";
 import { handleError } from "@/utils/error-handler";
+import { encodeWav16kMono } from "@/lib/audio-utils";
+
+const APPROVAL_STATUS: Record<ApprovalDecision, string> = {
+  allow: "✓ Approved",
+  deny: "✗ Denied",
+  ask: "Confirm in the terminal",
+};
 
 export function DictationWindow() {
   const appState = useAppState();
@@ -21,6 +35,44 @@ export function DictationWindow() {
     },
   });
 
+  const [refinementPreview, setRefinementPreview] = useState("");
+  const isRefiningRef = useRef(false);
+
+  // Tool calls waiting for a spoken or keyed answer, oldest first; recordings
+  // answer the first instead of being sent to Claude Code
+  const [approvalQueue, setApprovalQueue] = useState<ApprovalRequest[]>([]);
+  const approvalQueueRef = useRef<ApprovalRequest[]>([]);
+  const pendingApproval = approvalQueue[0] ?? null;
+  const pendingApprovalRef = useRef<ApprovalRequest | null>(null);
+
+  const updateApprovalQueue = (queue: ApprovalRequest[]) => {
+    approvalQueueRef.current = queue;
+    pendingApprovalRef.current = queue[0] ?? null;
+    setApprovalQueue(queue);
+  };
+
+  const removeApproval = (id: number) => {
+    updateApprovalQueue(approvalQueueRef.current.filter((approval) => approval.id !== id));
+  };
+
+  const answerApproval = async (id: number, answer: Promise<ApprovalDecision>) => {
+    try {
+      const decision = await answer;
+      removeApproval(id);
+      appState.setError(null);
+      appState.setStatus(APPROVAL_STATUS[decision]);
+      if (approvalQueueRef.current.length === 0) {
+        setTimeout(() => {
+          appState.hideWindow();
+        }, 1500);
+      }
+    } catch (error) {
+      const { message } = handleError(error);
+      appState.setError(message);
+      appState.setStatus("Press your hotkey to answer again");
+    }
+  };
+
   const recordingStateRef = useRef({
     isRecording: recording.isRecording,
     isTranscribing: recording.isTranscribing,
@@ -47,24 +99,38 @@ export function DictationWindow() {
       }
 
       appState.setStatus("Transcribing...");
-      const audioData = new Uint8Array(await audioBlob.arrayBuffer());
+      const isLocal = config.transcription.provider === "local";
+      const audioData = isLocal
+        ? await encodeWav16kMono(audioBlob)
+        : new Uint8Array(await audioBlob.arrayBuffer());
       
       const transcribedText = await apiService.transcribeAudio({
         audioData,
-        apiKey: config.groqApiKey,
+        mimeType: isLocal ? "audio/wav" : undefined,
       });
 
+      const approval = pendingApprovalRef.current;
+      if (approval) {
+        await answerApproval(approval.id, approvalService.answerSpoken(approval.id, transcribedText));
+        return;
+      }
+
       appState.setStatus("Refining prompt...");
-      const refinedPrompt = await apiService.refinePrompt({
-        text: transcribedText,
-        apiKey: config.anthropicApiKey,
-        systemPrompt: config.systemPrompt,
-      });
+      setRefinementPreview("");
+      isRefiningRef.current = true;
+      const refinedPrompt = await apiService
+        .refinePrompt({
+          text: transcribedText,
+          stream: config.refinement.stream,
+        })
+        .finally(() => {
+          isRefiningRef.current = false;
+        });
 
       if (appState.handsFreeMode.isActive) {
         // Inject into Claude Code session
         appState.setStatus("Sending to Claude Code...");
-        await invoke("inject_prompt_to_claude_session", { prompt: refinedPrompt });
+        await sessionService.injectPrompt(refinedPrompt, transcribedText);
         appState.setStatus("✓ Sent to Claude Code!");
         
         appState.setHandsFreeMode({ 
@@ -85,6 +151,11 @@ export function DictationWindow() {
         }, 2000);
       }
     } catch (error) {
+      if (String(error).includes("Refinement cancelled")) {
+        setRefinementPreview("");
+        appState.resetState();
+        return;
+      }
       const { message } = handleError(error);
       appState.setError(message);
       appState.setStatus("Error occurred");
@@ -100,9 +171,10 @@ export function DictationWindow() {
     const unlistenStart = listen("start-recording", async () => {
       appState.setStatus("Listening...");
       appState.setError(null);
+      setRefinementPreview("");
       
       if (!isConfigured()) {
-        appState.setError("API keys not configured. Open Settings to configure your Groq and Anthropic API keys.");
+        appState.setError("API keys not configured. Open Settings to add your transcription and refinement API keys.");
         appState.setStatus("Configuration needed");
         return;
       }
@@ -126,10 +198,68 @@ export function DictationWindow() {
     };
   }, [isConfigured, recording, appState]);
 
+  // Tool calls waiting for approval
+  useEffect(() => {
+    const unlistenRequested = listen<ApprovalRequest>("approval-requested", (event) => {
+      const queued = approvalQueueRef.current.length > 0;
+      updateApprovalQueue([...approvalQueueRef.current, event.payload]);
+      if (!queued) {
+        appState.setError(null);
+        appState.setStatus(`Approve ${event.payload.toolName}?`);
+      }
+    });
+
+    const unlistenResolved = listen<ApprovalResolved>("approval-resolved", (event) => {
+      const wasFirst = pendingApprovalRef.current?.id === event.payload.id;
+      removeApproval(event.payload.id);
+      if (wasFirst && event.payload.decision === null) {
+        if (recordingStateRef.current.isRecording) {
+          cleanupRecordingRef.current();
+        }
+        appState.setStatus("No answer - confirm in the terminal");
+      }
+    });
+
+    return () => {
+      unlistenRequested.then(fn => fn());
+      unlistenResolved.then(fn => fn());
+    };
+  }, [appState]);
+
+  // Show streamed refinement output as it arrives
+  useEffect(() => {
+    const unlistenDelta = listen<{ delta: string }>("refinement-delta", (event) => {
+      setRefinementPreview(prev => prev + event.payload.delta);
+    });
+
+    const unlistenComplete = listen<{ text: string; cancelled: boolean }>("refinement-complete", (event) => {
+      setRefinementPreview(event.payload.cancelled ? "" : event.payload.text);
+    });
+
+    return () => {
+      unlistenDelta.then(fn => fn());
+      unlistenComplete.then(fn => fn());
+    };
+  }, []);
+
   // Handle keyboard shortcuts
   useEffect(() => {
     const handleKeyDown = (event: KeyboardEvent) => {
+      const approval = pendingApprovalRef.current;
+      if (approval && (event.key === "y" || event.key === "n")) {
+        if (recordingStateRef.current.isRecording) {
+          cleanupRecordingRef.current();
+        }
+        // The app refuses to approve a truncated call; the terminal shows all of it
+        const decision = event.key === "n" ? "deny" : approval.truncated ? "ask" : "allow";
+        answerApproval(approval.id, approvalService.answer(approval.id, decision));
+        return;
+      }
+
       if (event.key === "Escape") {
+        if (isRefiningRef.current) {
+          apiService.cancelRefinement();
+        }
         if (recordingStateRef.current.isRecording || recordingStateRef.current.isTranscribing) {
           cleanupRecordingRef.current();
         }
@@ -169,6 +299,56 @@ export function DictationWindow() {
           )}
         </div>
 
+        {/* Streamed refinement preview */}
+        {refinementPreview && (
+          <div className="mb-2 max-h-24 overflow-y-auto rounded border border-white/10 bg-white/5 px-2 py-1 text-xs text-white/80 whitespace-pre-wrap">
+            {refinementPreview}
+          </div>
+        )}
+
+        {/* Tool call waiting for approval */}
+        {pendingApproval && (
+          <div className="mb-2 rounded border border-yellow-500/30 bg-yellow-900/20 px-3 py-2 text-xs">
+            <div className="font-medium text-yellow-300">
+              Claude Code wants to use {pendingApproval.toolName}
+              {approvalQueue.length > 1 && (
+                <span className="ml-1 text-white/50">(+{approvalQueue.length - 1} waiting)</span>
+              )}
+            </div>
+            <div className="mt-1 text-white/80">{pendingApproval.summary}</div>
+            {pendingApproval.detail && (
+              <div className="mt-1 max-h-24 overflow-y-auto font-mono text-white/80 break-all whitespace-pre-wrap">
+                {pendingApproval.detail}
+              </div>
+            )}
+            <div className="mt-1 text-white/50 break-all">{pendingApproval.cwd}</div>
+            <div className="mt-1 text-white/50">{pendingApproval.reason}</div>
+            <div className="mt-2 flex space-x-2">
+              {pendingApproval.truncated ? (
+                <button
+                  onClick={() => answerApproval(pendingApproval.id, approvalService.answer(pendingApproval.id, "ask"))}
+                  className="flex-1 rounded bg-white/10 px-3 py-1 text-white hover:bg-white/20"
+                >
+                  Confirm in terminal (Y)
+                </button>
+              ) : (
+                <button
+                  onClick={() => answerApproval(pendingApproval.id, approvalService.answer(pendingApproval.id, "allow"))}
+                  className="flex-1 rounded bg-green-600 px-3 py-1 text-white hover:bg-green-700"
+                >
+                  Approve (Y)
+                </button>
+              )}
+              <button
+                onClick={() => answerApproval(pendingApproval.id, approvalService.answer(pendingApproval.id, "deny"))}
+                className="flex-1 rounded bg-red-600 px-3 py-1 text-white hover:bg-red-700"
+              >
+                Deny (N)
+              </button>
+            </div>
+          </div>
+        )}
+
         {/* Audio visualizer */}
         <div className="mb-3 h-20">
           <AudioVisualizer
@@ -192,7 +372,7 @@ export function DictationWindow() {
             </div>
           ) : (
             <div className="text-center text-white/40 text-xs">
-              Press Enter to submit • Escape to abort
+              {pendingApproval ? (pendingApproval.truncated ? "Say deny or terminal • " : "Say approve or deny • ") : ""}Press Enter to submit • Escape to abort
             </div>
           )}
         </div>
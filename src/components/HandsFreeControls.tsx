@@ -2,12 +2,16 @@ import { useState, useEffect } from "react";
 import { useAppState } from "@/hooks/use-app-state";
 import { invoke } from "@tauri-apps/api/core";
 import { CLISetup } from "./CLISetup";
+import { sessionLabel, sessionService, type SessionInfo, type TurnSummary } from "@/services/sessions";
 
 export function HandsFreeControls() {
   const appState = useAppState();
   const [terminalInfo, setTerminalInfo] = useState<string>("");
   const [showDetails, setShowDetails] = useState(false);
   const [showSetup, setShowSetup] = useState(false);
+  const [sessions, setSessions] = useState<SessionInfo[]>([]);
+  const [targetSession, setTargetSession] = useState<string | null>(null);
+  const [lastTurn, setLastTurn] = useState<TurnSummary | null>(null);
 
   useEffect(() => {
     const updateTerminalInfo = async () => {
@@ -19,13 +23,42 @@ export function HandsFreeControls() {
       }
     };
 
+    const updateSessions = async () => {
+      try {
+        const sessions = await sessionService.list();
+        const target = await sessionService.getTarget();
+        setSessions(sessions);
+        setTargetSession(target);
+
+        // The selected session, otherwise the most recently active one
+        const summarized = target ?? sessions[0]?.sessionId;
+        setLastTurn(summarized ? await sessionService.summary(summarized) : null);
+      } catch (error) {
+        console.error("Failed to list Claude Code sessions:", error);
+      }
+    };
+
     if (appState.handsFreeMode.isActive) {
       updateTerminalInfo();
-      const interval = setInterval(updateTerminalInfo, 5000);
+      updateSessions();
+      const interval = setInterval(() => {
+        updateTerminalInfo();
+        updateSessions();
+      }, 5000);
       return () => clearInterval(interval);
     }
   }, [appState.handsFreeMode.isActive]);
 
+  const handleTargetChange = async (sessionId: string) => {
+    const target = sessionId === "" ? null : sessionId;
+    try {
+      await sessionService.setTarget(target);
+      setTargetSession(target);
+    } catch (error) {
+      console.error("Failed to select target session:", error);
+    }
+  };
+
   const handleToggleHandsFree = async () => {
     if (appState.handsFreeMode.isActive) {
       await appState.deactivateHandsFreeMode();
@@ -101,6 +134,22 @@ export function HandsFreeControls() {
 
       {appState.handsFreeMode.isActive && (
         <div className="space-y-2">
+          <div className="flex justify-between items-center text-xs text-white/70">
+            <span>Target session:</span>
+            <select
+              value={targetSession ?? ""}
+              onChange={(e) => handleTargetChange(e.target.value)}
+              className="bg-black/40 border border-white/20 rounded px-2 py-1 text-xs text-white"
+              title='Or start a prompt with "in the <project> project, ..."'
+            >
+              <option value="">Automatic (last stopped)</option>
+              {sessions.map((session) => (
+                <option key={session.sessionId} value={session.sessionId}>
+                  {sessionLabel(session)} ({session.status})
+                </option>
+              ))}
+            </select>
+          </div>
           <div className="flex justify-between text-xs text-white/70">
             <span>Cycles completed:</span>
             <span>{appState.handsFreeMode.cycleCount}</span>
@@ -109,6 +158,15 @@ export function HandsFreeControls() {
             <span>Auto-approvals:</span>
             <span>{appState.handsFreeMode.autoApprovalCount}</span>
           </div>
+          {lastTurn && (
+            <div className="flex justify-between text-xs text-white/70">
+              <span>Last turn:</span>
+              <span title={lastTurn.filesChanged.join("\n")}>
+                {Object.values(lastTurn.toolsUsed).reduce((sum, count) => sum + count, 0)} tool calls,{" "}
+                {lastTurn.filesChanged.length} files changed
+              </span>
+            </div>
+          )}
           {appState.handsFreeMode.lastCycleTime && (
             <div className="flex justify-between text-xs text-white/70">
               <span>Last cycle:</span>
@@ -136,6 +194,17 @@ export function HandsFreeControls() {
                 </div>
               </div>
               
+              {lastTurn && lastTurn.filesChanged.length > 0 && (
+                <div className="text-xs text-white/60">
+                  <div className="font-medium">Files Changed Last Turn:</div>
+                  <div className="font-mono bg-black/40 p-2 rounded text-xs mt-1">
+                    {lastTurn.filesChanged.map((file) => (
+                      <div key={file}>{file}</div>
+                    ))}
+                  </div>
+                </div>
+              )}
+
               <div className="text-xs text-white/60">
                 <div className="font-medium">Safety Status:</div>
                 <div className="text-green-400">✓ Safe operations auto-approved</div>
@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
@@ -13,26 +13,91 @@ import {
 
 import { useConfig } from "@/hooks/use-config";
 import { apiService } from "@/services/api";
+import type {
+  HandsFreeConfig,
+  RefinementProvider,
+  SecretName,
+  SpeechProvider,
+  TranscriptionProvider,
+} from "@/services/config";
+
+const HANDS_FREE_LIMITS: { key: keyof HandsFreeConfig; label: string }[] = [
+  { key: "maxCycles", label: "Max Cycles per Run" },
+  { key: "maxRunMinutes", label: "Max Run Minutes" },
+  { key: "maxRunTokens", label: "Max Run Tokens" },
+  { key: "idleResetMinutes", label: "Idle Reset (minutes)" },
+];
 
 export function SettingsWindow() {
-  const { config, updateConfig, resetConfig, isLoading } = useConfig();
+  const {
+    config,
+    updateConfig,
+    saveConfig,
+    secretStatus,
+    setSecret,
+    deleteSecret,
+    resetSystemPrompt,
+    isLoaded,
+    isLoading,
+    error,
+  } = useConfig();
   const [isRecordingHotkey, setIsRecordingHotkey] = useState(false);
   const [tempHotkey, setTempHotkey] = useState(config.hotkey);
 
+  useEffect(() => {
+    if (isLoaded) {
+      setTempHotkey(config.hotkey);
+    }
+  }, [isLoaded]);
+
+  // New key values typed into Settings; stored keys are never read back
+  const [keyDrafts, setKeyDrafts] = useState<Record<SecretName, string>>({
+    transcriptionApiKey: "",
+    refinementApiKey: "",
+  });
+
+  const [isDownloadingModel, setIsDownloadingModel] = useState(false);
+  const [modelStatus, setModelStatus] = useState<string | null>(null);
+
   const costs = apiService.getCosts();
 
+  const handleDownloadModel = async () => {
+    setIsDownloadingModel(true);
+    setModelStatus(null);
+    try {
+      const path = await apiService.downloadWhisperModel(
+        config.transcription.model || "base.en"
+      );
+      setModelStatus(`Model saved to ${path}`);
+    } catch (error) {
+      setModelStatus(error instanceof Error ? error.message : String(error));
+    } finally {
+      setIsDownloadingModel(false);
+    }
+  };
+
   const handleSave = async () => {
-    await updateConfig({
-      groqApiKey: config.groqApiKey,
-      anthropicApiKey: config.anthropicApiKey,
-      systemPrompt: config.systemPrompt,
-      hotkey: tempHotkey,
-    });
-    window.close();
+    for (const [name, value] of Object.entries(keyDrafts) as [SecretName, string][]) {
+      if (value.trim() && !(await setSecret(name, value))) {
+        return;
+      }
+    }
+    if (await saveConfig({ hotkey: tempHotkey })) {
+      window.close();
+    }
   };
 
+  const handleClearKey = async (name: SecretName) => {
+    if (await deleteSecret(name)) {
+      setKeyDrafts((prev) => ({ ...prev, [name]: "" }));
+    }
+  };
+
+  const keyPlaceholder = (name: SecretName, unsetText: string) =>
+    secretStatus[name] ? "Saved (enter a new key to replace it)" : unsetText;
+
   const handleResetPrompt = () => {
-    resetConfig();
+    resetSystemPrompt();
   };
 
   const startRecordingHotkey = () => {
@@ -87,30 +152,240 @@ export function SettingsWindow() {
           <CardHeader>
             <CardTitle>API Keys</CardTitle>
             <CardDescription>
-              Configure your API keys for speech-to-text and prompt refinement
+              Keys are kept in your system keychain (or an encrypted file) and are never shown again
+            </CardDescription>
+          </CardHeader>
+          <CardContent className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="groq-api-key">Transcription API Key</Label>
+              <div className="flex gap-2">
+                <Input
+                  id="groq-api-key"
+                  type="password"
+                  autoComplete="off"
+                  placeholder={keyPlaceholder(
+                    "transcriptionApiKey",
+                    "Enter your Groq, OpenAI or Deepgram API key"
+                  )}
+                  value={keyDrafts.transcriptionApiKey}
+                  onChange={(e) =>
+                    setKeyDrafts((prev) => ({ ...prev, transcriptionApiKey: e.target.value }))
+                  }
+                />
+                {secretStatus.transcriptionApiKey && (
+                  <Button variant="outline" onClick={() => handleClearKey("transcriptionApiKey")}>
+                    Clear
+                  </Button>
+                )}
+              </div>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="anthropic-api-key">Refinement API Key</Label>
+              <div className="flex gap-2">
+                <Input
+                  id="anthropic-api-key"
+                  type="password"
+                  autoComplete="off"
+                  placeholder={keyPlaceholder(
+                    "refinementApiKey",
+                    "Enter your Anthropic or OpenAI-compatible API key"
+                  )}
+                  value={keyDrafts.refinementApiKey}
+                  onChange={(e) =>
+                    setKeyDrafts((prev) => ({ ...prev, refinementApiKey: e.target.value }))
+                  }
+                />
+                {secretStatus.refinementApiKey && (
+                  <Button variant="outline" onClick={() => handleClearKey("refinementApiKey")}>
+                    Clear
+                  </Button>
+                )}
+              </div>
+            </div>
+          </CardContent>
+        </Card>
+
+        <Card>
+          <CardHeader>
+            <CardTitle>Transcription</CardTitle>
+            <CardDescription>
+              Choose the speech-to-text provider, or point Vocal at your own Whisper server
             </CardDescription>
           </CardHeader>
           <CardContent className="space-y-4">
             <div className="space-y-2">
-              <Label htmlFor="groq-api-key">Groq API Key</Label>
+              <Label htmlFor="transcription-provider">Provider</Label>
+              <select
+                id="transcription-provider"
+                value={config.transcription.provider}
+                onChange={(e) =>
+                  updateConfig({
+                    transcription: {
+                      ...config.transcription,
+                      provider: e.target.value as TranscriptionProvider,
+                    },
+                  })
+                }
+                className="border-input flex h-9 w-full rounded-md border bg-transparent px-3 py-1 text-sm"
+              >
+                <option value="groq">Groq</option>
+                <option value="openai">OpenAI</option>
+                <option value="deepgram">Deepgram</option>
+                <option value="openai-compatible">OpenAI-compatible server</option>
+                <option value="local">Local Whisper (offline)</option>
+              </select>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="transcription-base-url">Base URL</Label>
+              <Input
+                id="transcription-base-url"
+                placeholder="Provider default (e.g. http://localhost:8000/v1)"
+                value={config.transcription.baseUrl ?? ""}
+                onChange={(e) =>
+                  updateConfig({
+                    transcription: { ...config.transcription, baseUrl: e.target.value },
+                  })
+                }
+              />
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="transcription-model">Model</Label>
+              <Input
+                id="transcription-model"
+                placeholder="Provider default (e.g. whisper-large-v3)"
+                value={config.transcription.model ?? ""}
+                onChange={(e) =>
+                  updateConfig({
+                    transcription: { ...config.transcription, model: e.target.value },
+                  })
+                }
+              />
+            </div>
+            {config.transcription.provider === "local" && (
+              <>
+                <div className="space-y-2">
+                  <Label htmlFor="transcription-model-path">Model File</Label>
+                  <Input
+                    id="transcription-model-path"
+                    placeholder="~/.vocal/models/ggml-<model>.bin"
+                    value={config.transcription.modelPath ?? ""}
+                    onChange={(e) =>
+                      updateConfig({
+                        transcription: { ...config.transcription, modelPath: e.target.value },
+                      })
+                    }
+                  />
+                </div>
+                <Button
+                  variant="outline"
+                  onClick={handleDownloadModel}
+                  disabled={isDownloadingModel}
+                  className="w-full"
+                >
+                  {isDownloadingModel ? "Downloading..." : "Download Model"}
+                </Button>
+                {modelStatus && (
+                  <p className="text-sm text-muted-foreground">{modelStatus}</p>
+                )}
+              </>
+            )}
+          </CardContent>
+        </Card>
+
+        <Card>
+          <CardHeader>
+            <CardTitle>Refinement</CardTitle>
+            <CardDescription>
+              Choose the language model that cleans up your transcripts
+            </CardDescription>
+          </CardHeader>
+          <CardContent className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="refinement-provider">Provider</Label>
+              <select
+                id="refinement-provider"
+                value={config.refinement.provider}
+                onChange={(e) =>
+                  updateConfig({
+                    refinement: {
+                      ...config.refinement,
+                      provider: e.target.value as RefinementProvider,
+                    },
+                  })
+                }
+                className="border-input flex h-9 w-full rounded-md border bg-transparent px-3 py-1 text-sm"
+              >
+                <option value="anthropic">Anthropic</option>
+                <option value="openai-compatible">OpenAI-compatible server</option>
+                <option value="ollama">Ollama (local)</option>
+              </select>
+            </div>
+            <div className="space-y-2">
+              <Label htmlFor="refinement-base-url">Base URL</Label>
               <Input
-                id="groq-api-key"
-                type="password"
-                placeholder="Enter your Groq API key"
-                value={config.groqApiKey}
-                onChange={(e) => updateConfig({ groqApiKey: e.target.value })}
+                id="refinement-base-url"
+                placeholder="Provider default (e.g. http://localhost:11434)"
+                value={config.refinement.baseUrl ?? ""}
+                onChange={(e) =>
+                  updateConfig({
+                    refinement: { ...config.refinement, baseUrl: e.target.value },
+                  })
+                }
               />
             </div>
             <div className="space-y-2">
-              <Label htmlFor="anthropic-api-key">Anthropic API Key</Label>
+              <Label htmlFor="refinement-model">Model</Label>
               <Input
-                id="anthropic-api-key"
-                type="password"
-                placeholder="Enter your Anthropic API key"
-                value={config.anthropicApiKey}
-                onChange={(e) => updateConfig({ anthropicApiKey: e.target.value })}
+                id="refinement-model"
+                placeholder="Provider default (e.g. claude-sonnet-4-20250514)"
+                value={config.refinement.model ?? ""}
+                onChange={(e) =>
+                  updateConfig({
+                    refinement: { ...config.refinement, model: e.target.value },
+                  })
+                }
               />
             </div>
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="refinement-temperature">Temperature</Label>
+                <Input
+                  id="refinement-temperature"
+                  type="number"
+                  step="0.1"
+                  min="0"
+                  max="2"
+                  placeholder="Default"
+                  value={config.refinement.temperature ?? ""}
+                  onChange={(e) =>
+                    updateConfig({
+                      refinement: {
+                        ...config.refinement,
+                        temperature: e.target.value === "" ? undefined : Number(e.target.value),
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="refinement-max-tokens">Max Tokens</Label>
+                <Input
+                  id="refinement-max-tokens"
+                  type="number"
+                  min="1"
+                  placeholder="Default"
+                  value={config.refinement.maxTokens ?? ""}
+                  onChange={(e) =>
+                    updateConfig({
+                      refinement: {
+                        ...config.refinement,
+                        maxTokens: e.target.value === "" ? undefined : Number(e.target.value),
+                      },
+                    })
+                  }
+                />
+              </div>
+            </div>
           </CardContent>
         </Card>
 
@@ -155,6 +430,187 @@ export function SettingsWindow() {
           </CardContent>
         </Card>
 
+        <Card>
+          <CardHeader>
+            <CardTitle>Hands-Free Limits</CardTitle>
+            <CardDescription>
+              Pause hands-free mode when a session keeps looping. A run ends after
+              a pause longer than the idle reset; 0 disables a limit
+            </CardDescription>
+          </CardHeader>
+          <CardContent className="space-y-4">
+            <div className="grid grid-cols-2 gap-4">
+              {HANDS_FREE_LIMITS.map(({ key, label }) => (
+                <div key={key} className="space-y-2">
+                  <Label htmlFor={`hands-free-${key}`}>{label}</Label>
+                  <Input
+                    id={`hands-free-${key}`}
+                    type="number"
+                    min="0"
+                    value={config.handsFree[key]}
+                    onChange={(e) =>
+                      updateConfig({
+                        handsFree: { ...config.handsFree, [key]: Number(e.target.value) },
+                      })
+                    }
+                  />
+                </div>
+              ))}
+            </div>
+          </CardContent>
+        </Card>
+
+        <Card>
+          <CardHeader>
+            <CardTitle>Speech</CardTitle>
+            <CardDescription>
+              Read approvals and Claude's answers out loud in hands-free mode
+            </CardDescription>
+          </CardHeader>
+          <CardContent className="space-y-4">
+            <div className="space-y-2">
+              <Label htmlFor="speech-provider">Voice Engine</Label>
+              <select
+                id="speech-provider"
+                value={config.speech.provider}
+                onChange={(e) =>
+                  updateConfig({
+                    speech: { ...config.speech, provider: e.target.value as SpeechProvider },
+                  })
+                }
+                className="border-input flex h-9 w-full rounded-md border bg-transparent px-3 py-1 text-sm"
+              >
+                <option value="system">System default</option>
+                <option value="say">say (macOS)</option>
+                <option value="espeak">espeak</option>
+                <option value="command">Custom command (local engine)</option>
+              </select>
+            </div>
+            {config.speech.provider === "command" && (
+              <div className="space-y-2">
+                <Label htmlFor="speech-command">Command</Label>
+                <Input
+                  id="speech-command"
+                  placeholder="piper --model en_US-amy-medium.onnx --output-raw | aplay -r 22050 -f S16_LE"
+                  value={config.speech.command ?? ""}
+                  onChange={(e) =>
+                    updateConfig({ speech: { ...config.speech, command: e.target.value } })
+                  }
+                />
+                <p className="text-sm text-muted-foreground">
+                  Run through the shell with the text on stdin
+                </p>
+              </div>
+            )}
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="speech-voice">Voice</Label>
+                <Input
+                  id="speech-voice"
+                  placeholder="Default"
+                  value={config.speech.voice ?? ""}
+                  onChange={(e) =>
+                    updateConfig({ speech: { ...config.speech, voice: e.target.value } })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="speech-rate">Words per Minute</Label>
+                <Input
+                  id="speech-rate"
+                  type="number"
+                  min="1"
+                  placeholder="Default"
+                  value={config.speech.rate ?? ""}
+                  onChange={(e) =>
+                    updateConfig({
+                      speech: {
+                        ...config.speech,
+                        rate: e.target.value === "" ? undefined : Number(e.target.value),
+                      },
+                    })
+                  }
+                />
+              </div>
+            </div>
+            <label className="flex items-center space-x-2 text-sm">
+              <input
+                type="checkbox"
+                checked={config.speech.readback}
+                onChange={(e) =>
+                  updateConfig({ speech: { ...config.speech, readback: e.target.checked } })
+                }
+              />
+              <span>Read Claude's final response out before recording restarts</span>
+            </label>
+            <label className="flex items-center space-x-2 text-sm">
+              <input
+                type="checkbox"
+                checked={config.speech.summarize}
+                disabled={!config.speech.readback}
+                onChange={(e) =>
+                  updateConfig({ speech: { ...config.speech, summarize: e.target.checked } })
+                }
+              />
+              <span>Summarize it with the refinement model first</span>
+            </label>
+          </CardContent>
+        </Card>
+
+        <Card>
+          <CardHeader>
+            <CardTitle>Git Safety</CardTitle>
+            <CardDescription>
+              Checkpoint the working tree before every hands-free prompt and ask
+              before any change on a protected branch
+            </CardDescription>
+          </CardHeader>
+          <CardContent className="space-y-4">
+            <label className="flex items-center space-x-2 text-sm">
+              <input
+                type="checkbox"
+                checked={config.git.enabled}
+                onChange={(e) =>
+                  updateConfig({ git: { ...config.git, enabled: e.target.checked } })
+                }
+              />
+              <span>Enable git checkpoints and branch protection</span>
+            </label>
+            <div className="grid grid-cols-2 gap-4">
+              <div className="space-y-2">
+                <Label htmlFor="git-protected-branches">Protected Branches</Label>
+                <Input
+                  id="git-protected-branches"
+                  placeholder="main, release/*"
+                  disabled={!config.git.enabled}
+                  value={config.git.protectedBranches.join(", ")}
+                  onChange={(e) =>
+                    updateConfig({
+                      git: {
+                        ...config.git,
+                        protectedBranches: e.target.value.split(",").map((branch) => branch.trim()),
+                      },
+                    })
+                  }
+                />
+              </div>
+              <div className="space-y-2">
+                <Label htmlFor="git-max-checkpoints">Checkpoints Kept</Label>
+                <Input
+                  id="git-max-checkpoints"
+                  type="number"
+                  min="1"
+                  disabled={!config.git.enabled}
+                  value={config.git.maxCheckpoints}
+                  onChange={(e) =>
+                    updateConfig({ git: { ...config.git, maxCheckpoints: Number(e.target.value) } })
+                  }
+                />
+              </div>
+            </div>
+          </CardContent>
+        </Card>
+
         <Card>
           <CardHeader>
             <CardTitle>System Prompt</CardTitle>
@@ -208,6 +664,10 @@ export function SettingsWindow() {
           </CardContent>
         </Card>
 
+        {error && (
+          <p className="text-sm text-destructive">{error}</p>
+        )}
+
         <div className="flex justify-end space-x-3">
           <Button variant="outline" onClick={() => window.close()}>
             Cancel
@@ -164,31 +164,54 @@ export function useAppState() {
       }
     });
 
+    // Hands-free mode switched on or off outside the app, e.g. by a cycle limit
+    const unlistenHandsFree = listen<{ active: boolean; reason: string | null }>(
+      "hands-free-changed",
+      (event) => {
+        const { active, reason } = event.payload;
+        setHandsFreeMode({ isActive: active, currentPhase: reason ? 'error' : 'idle' });
+        if (reason) {
+          setError(reason);
+        }
+      }
+    );
+
+    // Emergency stop from the kill-switch file or another window
+    const unlistenEmergency = listen("emergency-stop", () => {
+      setHandsFreeMode({
+        isActive: false,
+        emergencyStopTriggered: true,
+        currentPhase: 'error',
+      });
+    });
+
+    // A cycle trigger written while the app was not running is only picked
+    // up once someone listens for the restart it causes
+    if (state.handsFreeMode.isActive) {
+      unlistenRestart
+        .then(() => invoke("check_cycle_trigger"))
+        .catch((error) => console.error("Failed to check for a cycle trigger:", error));
+    }
+
     return () => {
       unlistenStart.then(fn => fn());
       unlistenAbort.then(fn => fn());
       unlistenRestart.then(fn => fn());
+      unlistenHandsFree.then(fn => fn());
+      unlistenEmergency.then(fn => fn());
     };
   }, [state.handsFreeMode.isActive, state.handsFreeMode.cycleCount]);
 
-  // Cycle trigger checking
+  // Hands-free mode may still be on from before the app started
   useEffect(() => {
-    if (!state.handsFreeMode.isActive) return;
-
-    const checkCycleTrigger = async () => {
-      try {
-        const triggered = await invoke("check_cycle_trigger") as boolean;
-        if (triggered) {
-          console.log("Cycle trigger detected - recording will restart");
+    invoke<boolean>("get_hands_free_status")
+      .then((isActive) => {
+        if (isActive) {
+          setHandsFreeMode({ isActive });
         }
-      } catch (error) {
-        console.error("Failed to check cycle trigger:", error);
-      }
-    };
-
-    const interval = setInterval(checkCycleTrigger, 1000);
-    return () => clearInterval(interval);
-  }, [state.handsFreeMode.isActive]);
+      })
+      .catch((error) => console.error("Failed to read hands-free status:", error));
+  }, []);
 
   return {
     ...state,
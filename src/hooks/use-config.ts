@@ -1,57 +1,122 @@
-import { useState, useEffect } from "react";
-import { configService, AppConfig } from "@/services/config";
-import { invoke } from "@tauri-apps/api/core";
+import { useState, useEffect, useCallback } from "react";
+import { listen } from "@tauri-apps/api/event";
+import {
+  configService,
+  AppConfig,
+  DEFAULT_CONFIG,
+  EMPTY_SECRET_STATUS,
+  SecretName,
+  SecretStatus,
+} from "@/services/config";
 
 export function useConfig() {
-  const [config, setConfig] = useState<AppConfig>(configService.getConfig());
+  const [config, setConfig] = useState<AppConfig>(DEFAULT_CONFIG);
+  const [secretStatus, setSecretStatus] = useState<SecretStatus>(EMPTY_SECRET_STATUS);
+  const [isLoaded, setIsLoaded] = useState(false);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
 
-  const updateConfig = async (newConfig: Partial<AppConfig>) => {
+  // Edits the in-memory copy; call saveConfig to persist
+  const updateConfig = (changes: Partial<AppConfig>) => {
+    setConfig(prev => ({ ...prev, ...changes }));
+  };
+
+  const saveConfig = async (changes: Partial<AppConfig> = {}): Promise<boolean> => {
     setIsLoading(true);
     setError(null);
     
     try {
-      configService.updateConfig(newConfig);
-      const updatedConfig = configService.getConfig();
+      const updatedConfig = { ...config, ...changes };
+      await configService.saveConfig(updatedConfig);
       setConfig(updatedConfig);
-
-      // Update global shortcut if hotkey changed
-      if (newConfig.hotkey) {
-        await invoke("update_global_shortcut", { shortcut: newConfig.hotkey });
-      }
+      return true;
     } catch (err) {
-      setError(err instanceof Error ? err.message : "Failed to update configuration");
+      setError(err instanceof Error ? err.message : String(err));
+      return false;
     } finally {
       setIsLoading(false);
     }
   };
 
-  const resetConfig = () => {
-    configService.resetToDefaults();
-    setConfig(configService.getConfig());
+  // Keys are write-only from here: the backend stores them and reports back via secrets-changed
+  const setSecret = async (name: SecretName, value: string): Promise<boolean> => {
+    setError(null);
+    try {
+      await configService.setSecret(name, value);
+      return true;
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      return false;
+    }
   };
 
-  const validateConfig = () => {
-    return configService.validateConfig();
+  const deleteSecret = async (name: SecretName): Promise<boolean> => {
+    setError(null);
+    try {
+      await configService.deleteSecret(name);
+      return true;
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+      return false;
+    }
   };
 
-  const isConfigured = () => {
-    return configService.isConfigured();
+  // Restores the default system prompt without touching keys or providers
+  const resetSystemPrompt = async () => {
+    try {
+      const defaults = await configService.getDefaults();
+      updateConfig({ systemPrompt: defaults.systemPrompt });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
   };
 
+  const validateConfig = () => {
+    return configService.validateConfig(config, secretStatus);
+  };
+
+  const isConfigured = useCallback(() => {
+    return configService.isConfigured(config, secretStatus);
+  }, [config, secretStatus]);
+
   useEffect(() => {
-    // Load config on mount
-    setConfig(configService.getConfig());
+    // Load config on mount; the legacy migration may store keys, so read their status after it
+    configService
+      .getConfig()
+      .then(async (loaded) => {
+        setConfig(loaded);
+        setSecretStatus(await configService.getSecretStatus());
+      })
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)))
+      .finally(() => setIsLoaded(true));
+
+    // Pick up changes saved from another window
+    const unlistenChanged = listen<AppConfig>("config-changed", (event) => {
+      setConfig(event.payload);
+    });
+
+    const unlistenSecrets = listen<SecretStatus>("secrets-changed", (event) => {
+      setSecretStatus(event.payload);
+    });
+
+    return () => {
+      unlistenChanged.then(fn => fn());
+      unlistenSecrets.then(fn => fn());
+    };
   }, []);
 
   return {
     config,
     updateConfig,
-    resetConfig,
+    saveConfig,
+    secretStatus,
+    setSecret,
+    deleteSecret,
+    resetSystemPrompt,
     validateConfig,
     isConfigured,
+    isLoaded,
     isLoading,
     error,
   };
-}
\ No newline at end of file
+}
@@ -48,3 +48,52 @@ export const recordAudio = (function (): RecordAudioType {
 
   return func as RecordAudioType;
 })();
+
+const WHISPER_SAMPLE_RATE = 16000;
+
+/**
+ * Decodes a recording and re-encodes it as 16 kHz mono 16-bit PCM WAV,
+ * the format the local Whisper engine expects.
+ */
+export async function encodeWav16kMono(blob: Blob): Promise<Uint8Array> {
+  const decodeContext = new AudioContext();
+  const decoded = await decodeContext.decodeAudioData(await blob.arrayBuffer());
+  await decodeContext.close();
+
+  const frameCount = Math.ceil(decoded.duration * WHISPER_SAMPLE_RATE);
+  const offline = new OfflineAudioContext(1, frameCount, WHISPER_SAMPLE_RATE);
+  const source = offline.createBufferSource();
+  source.buffer = decoded;
+  source.connect(offline.destination);
+  source.start();
+  const samples = (await offline.startRendering()).getChannelData(0);
+
+  const buffer = new ArrayBuffer(44 + samples.length * 2);
+  const view = new DataView(buffer);
+  const writeString = (offset: number, value: string) => {
+    for (let i = 0; i < value.length; i++) {
+      view.setUint8(offset + i, value.charCodeAt(i));
+    }
+  };
+
+  writeString(0, "RIFF");
+  view.setUint32(4, 36 + samples.length * 2, true);
+  writeString(8, "WAVE");
+  writeString(12, "fmt ");
+  view.setUint32(16, 16, true);
+  view.setUint16(20, 1, true); // PCM
+  view.setUint16(22, 1, true); // mono
+  view.setUint32(24, WHISPER_SAMPLE_RATE, true);
+  view.setUint32(28, WHISPER_SAMPLE_RATE * 2, true);
+  view.setUint16(32, 2, true);
+  view.setUint16(34, 16, true);
+  writeString(36, "data");
+  view.setUint32(40, samples.length * 2, true);
+
+  for (let i = 0; i < samples.length; i++) {
+    const sample = Math.max(-1, Math.min(1, samples[i]));
+    view.setInt16(44 + i * 2, sample < 0 ? sample * 0x8000 : sample * 0x7fff, true);
+  }
+
+  return new Uint8Array(buffer);
+}
@@ -14,11 +14,14 @@ export const APP_CONFIG = {
   },
 } as const;
 
+/** Legacy localStorage keys, read once to migrate into the Rust config store. */
 export const STORAGE_KEYS = {
   GROQ_API_KEY: "groq_api_key",
   ANTHROPIC_API_KEY: "anthropic_api_key",
   SYSTEM_PROMPT: "system_prompt",
   HOTKEY: "hotkey",
+  TRANSCRIPTION: "transcription",
+  REFINEMENT: "refinement",
 } as const;
 
 export const DEFAULT_HOTKEY = "CommandOrControl+Shift+V";
@@ -1,17 +1,15 @@
 import { invoke } from "@tauri-apps/api/core";
 import { APP_CONFIG } from "@/lib/constants";
 
+// Providers, API keys and the system prompt come from the Rust config store
 export interface TranscriptionOptions {
   audioData: Uint8Array;
-  apiKey: string;
-  model?: string;
+  mimeType?: string;
 }
 
 export interface RefinementOptions {
   text: string;
-  apiKey: string;
-  systemPrompt?: string;
-  model?: string;
+  stream?: boolean;
 }
 
 export interface ApiCosts {
@@ -40,7 +38,7 @@ export class ApiService {
       const startTime = Date.now();
       const result = await invoke<string>("transcribe_audio", {
         audioData: Array.from(options.audioData),
-        apiKey: options.apiKey,
+        mimeType: options.mimeType,
       });
       
       const duration = Date.now() - startTime;
@@ -54,12 +52,19 @@ export class ApiService {
     }
   }
 
+  async downloadWhisperModel(model: string): Promise<string> {
+    try {
+      return await invoke<string>("download_whisper_model", { model });
+    } catch (error) {
+      throw new Error(`Model download failed: ${error}`);
+    }
+  }
+
   async refinePrompt(options: RefinementOptions): Promise<string> {
     try {
-      const result = await invoke<string>("refine_prompt", {
+      const command = options.stream ? "refine_prompt_stream" : "refine_prompt";
+      const result = await invoke<string>(command, {
         text: options.text,
-        apiKey: options.apiKey,
-        systemPrompt: options.systemPrompt,
       });
       
       const estimatedCost = this.calculateRefinementCost(options.text.length, result.length);
@@ -72,6 +77,10 @@ export class ApiService {
     }
   }
 
+  async cancelRefinement(): Promise<boolean> {
+    return invoke<boolean>("cancel_refinement");
+  }
+
   private calculateTranscriptionCost(audioSize: number, _duration: number): number {
     // Estimate audio duration from size (rough approximation)
     const estimatedMinutes = audioSize / (1024 * 1024 * 0.5); // ~0.5MB per minute estimate
@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type ApprovalDecision = "allow" | "deny" | "ask";
+
+/** A tool call the safety rules want the user to validate. */
+export interface ApprovalRequest {
+  id: number;
+  sessionId: string;
+  cwd: string;
+  toolName: string;
+  summary: string;
+  /** The full command or file path */
+  detail: string | null;
+  /** The summary was cut, so only the terminal can approve the call */
+  truncated: boolean;
+  reason: string;
+  timeoutSecs: number;
+}
+
+/** Sent when an approval is answered; `decision` is null when it timed out. */
+export interface ApprovalResolved {
+  id: number;
+  decision: ApprovalDecision | null;
+}
+
+export class ApprovalService {
+  private static instance: ApprovalService;
+
+  static getInstance(): ApprovalService {
+    if (!ApprovalService.instance) {
+      ApprovalService.instance = new ApprovalService();
+    }
+    return ApprovalService.instance;
+  }
+
+  async answer(id: number, decision: ApprovalDecision): Promise<ApprovalDecision> {
+    return invoke<ApprovalDecision>("answer_approval", { id, decision });
+  }
+
+  /** Rejects when the transcript contains no recognizable answer. */
+  async answerSpoken(id: number, transcript: string): Promise<ApprovalDecision> {
+    return invoke<ApprovalDecision>("answer_approval", { id, transcript });
+  }
+}
+
+export const approvalService = ApprovalService.getInstance();
@@ -1,65 +1,128 @@
-export interface AppConfig {
-  groqApiKey: string;
-  anthropicApiKey: string;
-  systemPrompt: string;
-  hotkey: string;
+export type TranscriptionProvider =
+  | "groq"
+  | "openai"
+  | "deepgram"
+  | "openai-compatible"
+  | "local";
+
+export interface TranscriptionConfig {
+  provider: TranscriptionProvider;
+  baseUrl?: string;
+  model?: string;
+  modelPath?: string;
 }
 
-export const DEFAULT_SYSTEM_PROMPT = `You are an intelligent transcript processor for AI coding assistance. Your job is to analyze the input and apply the appropriate level of processing based on complexity and context.
-
-ANALYSIS STEP - First determine the processing level needed:
-
-MINIMAL PROCESSING (clean only):
-- Single sentence or simple request
-- Simple adjustments/modifications (e.g., "change the color to blue", "fix that bug")
-- Quick clarifications or short responses
-- When user is making small tweaks to existing work
+export type RefinementProvider = "anthropic" | "openai-compatible" | "ollama";
 
-ENHANCED PROCESSING (structure and format):
-- Multiple tasks, requests, or topics mentioned
-- Project initiation or planning discussions
-- Complex requirements with multiple components
-- Long explanations that would benefit from organization
-- When user is describing a new feature, system, or workflow
+export interface RefinementConfig {
+  provider: RefinementProvider;
+  baseUrl?: string;
+  model?: string;
+  temperature?: number;
+  maxTokens?: number;
+  /** Stream partial output into the dictation window while refining. */
+  stream?: boolean;
+}
 
-CONTEXT CLUES for processing level:
-- Project start indicators: "I want to build", "create a new", "let's start", "I need to develop"
-- Simple adjustment indicators: "change the", "fix this", "update that", "make it"
-- Multiple tasks: "and then", "also", "another thing", lists of requirements
-- Complexity: technical specifications, multiple conditions, detailed workflows
+/**
+ * Runaway-loop limits for hands-free mode. A run is a series of cycles in one
+ * session without a pause longer than `idleResetMinutes`; 0 disables a limit.
+ */
+export interface HandsFreeConfig {
+  maxCycles: number;
+  maxRunMinutes: number;
+  maxRunTokens: number;
+  idleResetMinutes: number;
+}
 
-FOR MINIMAL PROCESSING:
-- Remove filler words and phrases (um, uh, like, you know, etc.)
-- Remove redundant sentence starters (Yeah, but..., So, um..., Well, like...)
-- Fix speech recognition errors using context clues (especially coding terms: "hardcore" → "hardcoded", "react" → "React", etc.)
-- Remove unnecessary repetitive phrases ("I don't know" repeated multiple times)
-- Clean up redundant word clusters ("like the thing, the freaking" → just the main noun)
-- Improve flow by combining or streamlining related thoughts
-- Add proper punctuation
-- Preserve the core meaning and all substantive content
+export type SpeechProvider = "system" | "say" | "espeak" | "command";
+
+/**
+ * Text-to-speech for approvals and for reading Claude's final response out
+ * in hands-free mode. `command` runs a local engine with the text on stdin.
+ */
+export interface SpeechConfig {
+  provider: SpeechProvider;
+  voice?: string;
+  /** Words per minute */
+  rate?: number;
+  command?: string;
+  readback: boolean;
+  /** Condense the response through the refinement backend first. */
+  summarize: boolean;
+  maxChars: number;
+}
 
-FOR ENHANCED PROCESSING:
-- Apply all minimal processing rules above, PLUS:
-- Break down multiple tasks into bullet points or numbered lists
-- Add clear structure with headings if appropriate
-- Organize requirements, features, or steps logically
-- Use formatting that makes complex requests easier for AI to understand
+/**
+ * Git safety for hands-free mode: a checkpoint of the working tree under
+ * `refs/vocal/checkpoints/` at every prompt, and no auto-approval on
+ * protected branches (names or globs).
+ */
+export interface GitConfig {
+  enabled: boolean;
+  protectedBranches: string[];
+  maxCheckpoints: number;
+}
 
-IMPORTANT: Be context-aware for technical corrections. In coding contexts, interpret speech recognition errors appropriately (hardcore→hardcoded, react→React, etc.).
+export interface AppConfig {
+  systemPrompt: string;
+  hotkey: string;
+  transcription: TranscriptionConfig;
+  refinement: RefinementConfig;
+  handsFree: HandsFreeConfig;
+  speech: SpeechConfig;
+  git: GitConfig;
+}
 
-Always preserve the complete original meaning and intent. Never remove substantive content, only clean up delivery for clarity.
+/** API keys held by the Rust secrets store (OS keyring or encrypted file). */
+export type SecretName = "transcriptionApiKey" | "refinementApiKey";
 
-Return only the processed text with no explanations.`;
+/** Which keys are stored. Key values never leave the backend. */
+export type SecretStatus = Record<SecretName, boolean>;
 
+import { invoke } from "@tauri-apps/api/core";
 import { DEFAULT_HOTKEY, STORAGE_KEYS } from "@/lib/constants";
 
+/** Providers that run without an API key. */
+export const KEYLESS_TRANSCRIPTION_PROVIDERS: TranscriptionProvider[] = [
+  "openai-compatible",
+  "local",
+];
+
+/** Placeholder until the Rust-side configuration has loaded. */
 export const DEFAULT_CONFIG: AppConfig = {
-  groqApiKey: "",
-  anthropicApiKey: "",
-  systemPrompt: DEFAULT_SYSTEM_PROMPT,
+  systemPrompt: "",
   hotkey: DEFAULT_HOTKEY,
+  transcription: { provider: "groq" },
+  refinement: { provider: "anthropic", stream: true },
+  handsFree: {
+    maxCycles: 10,
+    maxRunMinutes: 30,
+    maxRunTokens: 2_000_000,
+    idleResetMinutes: 5,
+  },
+  speech: {
+    provider: "system",
+    readback: true,
+    summarize: false,
+    maxChars: 600,
+  },
+  git: {
+    enabled: false,
+    protectedBranches: ["main", "master"],
+    maxCheckpoints: 50,
+  },
 };
 
+export const EMPTY_SECRET_STATUS: SecretStatus = {
+  transcriptionApiKey: false,
+  refinementApiKey: false,
+};
+
+/**
+ * Reads and writes the configuration owned by the Rust backend
+ * (`~/.vocal/config.toml`).
+ */
 export class ConfigService {
   private static instance: ConfigService;
 
@@ -70,54 +133,98 @@ export class ConfigService {
     return ConfigService.instance;
   }
 
-  getConfig(): AppConfig {
-    return {
-      groqApiKey:
-        localStorage.getItem(STORAGE_KEYS.GROQ_API_KEY) ||
-        DEFAULT_CONFIG.groqApiKey,
-      anthropicApiKey:
-        localStorage.getItem(STORAGE_KEYS.ANTHROPIC_API_KEY) ||
-        DEFAULT_CONFIG.anthropicApiKey,
-      systemPrompt:
-        localStorage.getItem(STORAGE_KEYS.SYSTEM_PROMPT) ||
-        DEFAULT_CONFIG.systemPrompt,
-      hotkey:
-        localStorage.getItem(STORAGE_KEYS.HOTKEY) || DEFAULT_CONFIG.hotkey,
-    };
+  async getConfig(): Promise<AppConfig> {
+    const config = await invoke<AppConfig>("get_config");
+    return this.migrateLegacyConfig(config);
+  }
+
+  async saveConfig(config: AppConfig): Promise<void> {
+    await invoke("set_config", { config });
   }
 
-  updateConfig(config: Partial<AppConfig>): void {
+  async getDefaults(): Promise<AppConfig> {
+    return invoke<AppConfig>("get_default_config");
+  }
 
-    if (config.groqApiKey !== undefined) {
-      localStorage.setItem(STORAGE_KEYS.GROQ_API_KEY, config.groqApiKey);
-    }
-    if (config.anthropicApiKey !== undefined) {
-      localStorage.setItem(
-        STORAGE_KEYS.ANTHROPIC_API_KEY,
-        config.anthropicApiKey
-      );
-    }
-    if (config.systemPrompt !== undefined) {
-      localStorage.setItem(STORAGE_KEYS.SYSTEM_PROMPT, config.systemPrompt);
+  async getSecretStatus(): Promise<SecretStatus> {
+    return invoke<SecretStatus>("get_secret_status");
+  }
+
+  async setSecret(name: SecretName, value: string): Promise<void> {
+    await invoke("set_secret", { name, value });
+  }
+
+  async deleteSecret(name: SecretName): Promise<void> {
+    await invoke("delete_secret", { name });
+  }
+
+  /**
+   * Earlier versions kept settings in webview localStorage. Move anything
+   * found there into the Rust store once, then forget it. API keys go to the
+   * secrets store rather than the config file.
+   */
+  private async migrateLegacyConfig(config: AppConfig): Promise<AppConfig> {
+    const legacyKeys = Object.values(STORAGE_KEYS);
+    if (!legacyKeys.some((key) => localStorage.getItem(key) !== null)) {
+      return config;
     }
-    if (config.hotkey !== undefined) {
-      localStorage.setItem(STORAGE_KEYS.HOTKEY, config.hotkey);
+
+    const readObject = <T extends object>(key: string, fallback: T): T => {
+      try {
+        const stored = localStorage.getItem(key);
+        return stored ? { ...fallback, ...JSON.parse(stored) } : fallback;
+      } catch {
+        return fallback;
+      }
+    };
+
+    const legacySecrets: [SecretName, string | null][] = [
+      ["transcriptionApiKey", localStorage.getItem(STORAGE_KEYS.GROQ_API_KEY)],
+      ["refinementApiKey", localStorage.getItem(STORAGE_KEYS.ANTHROPIC_API_KEY)],
+    ];
+    for (const [name, value] of legacySecrets) {
+      if (value) {
+        await this.setSecret(name, value);
+      }
     }
+
+    const migrated: AppConfig = {
+      systemPrompt: localStorage.getItem(STORAGE_KEYS.SYSTEM_PROMPT) || config.systemPrompt,
+      hotkey: localStorage.getItem(STORAGE_KEYS.HOTKEY) || config.hotkey,
+      transcription: readObject(STORAGE_KEYS.TRANSCRIPTION, config.transcription),
+      refinement: readObject(STORAGE_KEYS.REFINEMENT, config.refinement),
+    };
+
+    await this.saveConfig(migrated);
+    legacyKeys.forEach((key) => localStorage.removeItem(key));
+    return migrated;
   }
 
-  isConfigured(): boolean {
-    const config = this.getConfig();
-    return !!(config.groqApiKey && config.anthropicApiKey);
+  isConfigured(config: AppConfig, secrets: SecretStatus): boolean {
+    const hasTranscriptionKey =
+      secrets.transcriptionApiKey ||
+      KEYLESS_TRANSCRIPTION_PROVIDERS.includes(config.transcription.provider);
+    const hasRefinementKey =
+      secrets.refinementApiKey || config.refinement.provider === "ollama";
+    return hasTranscriptionKey && hasRefinementKey;
   }
 
-  validateConfig(): { isValid: boolean; errors: string[] } {
-    const config = this.getConfig();
+  validateConfig(
+    config: AppConfig,
+    secrets: SecretStatus
+  ): { isValid: boolean; errors: string[] } {
     const errors: string[] = [];
 
-    if (!config.groqApiKey) {
-      errors.push("Groq API key is required");
+    if (
+      !secrets.transcriptionApiKey &&
+      !KEYLESS_TRANSCRIPTION_PROVIDERS.includes(config.transcription.provider)
+    ) {
+      errors.push("Transcription API key is required");
+    }
+    if (config.transcription.provider === "openai-compatible" && !config.transcription.baseUrl) {
+      errors.push("Transcription server URL is required");
     }
-    if (!config.anthropicApiKey) {
+    if (!secrets.refinementApiKey && config.refinement.provider === "anthropic") {
       errors.push("Anthropic API key is required");
     }
     if (!config.systemPrompt) {
@@ -132,10 +239,6 @@ export class ConfigService {
       errors,
     };
   }
-
-  resetToDefaults(): void {
-    this.updateConfig(DEFAULT_CONFIG);
-  }
 }
 
 export const configService = ConfigService.getInstance();
@@ -0,0 +1,49 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** A past dictation recorded in `~/.vocal/history.jsonl`. */
+export interface HistoryEntry {
+  id: string;
+  createdAt: string;
+  rawTranscript: string;
+  refinedPrompt?: string | null;
+  targetSession?: string | null;
+  transcriptionProvider?: string | null;
+  transcriptionMs?: number | null;
+  refinementProvider?: string | null;
+  refinementMs?: number | null;
+}
+
+export class HistoryService {
+  private static instance: HistoryService;
+
+  static getInstance(): HistoryService {
+    if (!HistoryService.instance) {
+      HistoryService.instance = new HistoryService();
+    }
+    return HistoryService.instance;
+  }
+
+  /** Newest first. */
+  async list(limit?: number, offset?: number): Promise<HistoryEntry[]> {
+    return invoke<HistoryEntry[]>("list_history", { limit, offset });
+  }
+
+  async search(query: string, limit?: number): Promise<HistoryEntry[]> {
+    return invoke<HistoryEntry[]>("search_history", { query, limit });
+  }
+
+  /**
+   * Sends the `n`th most recent refined prompt (1 = latest) to the Claude
+   * session again, or `editedPrompt` in its place. Resolves to the text sent.
+   */
+  async resendRecentPrompt(n: number, editedPrompt?: string): Promise<string> {
+    return invoke<string>("resend_recent_prompt", { n, editedPrompt });
+  }
+
+  /** Resolves to false when the entry no longer exists. */
+  async delete(id: string): Promise<boolean> {
+    return invoke<boolean>("delete_history_entry", { id });
+  }
+}
+
+export const historyService = HistoryService.getInstance();
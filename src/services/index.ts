@@ -1,5 +1,23 @@
 export { apiService, ApiService } from "./api";
 export type { TranscriptionOptions, RefinementOptions, ApiCosts } from "./api";
 
-export { configService, ConfigService, DEFAULT_SYSTEM_PROMPT, DEFAULT_CONFIG } from "./config";
-export type { AppConfig } from "./config";
\ No newline at end of file
+export { configService, ConfigService, DEFAULT_CONFIG, EMPTY_SECRET_STATUS } from "./config";
+export type {
+  AppConfig,
+  GitConfig,
+  HandsFreeConfig,
+  RefinementConfig,
+  RefinementProvider,
+  SecretName,
+  SecretStatus,
+  SpeechConfig,
+  SpeechProvider,
+  TranscriptionConfig,
+  TranscriptionProvider,
+} from "./config";
+export { historyService, HistoryService } from "./history";
+export type { HistoryEntry } from "./history";
+export { sessionService, SessionService, sessionLabel } from "./sessions";
+export type { SessionInfo, SessionStatus, TokenUsage, TurnSummary } from "./sessions";
+export { approvalService, ApprovalService } from "./approvals";
+export type { ApprovalDecision, ApprovalRequest, ApprovalResolved } from "./approvals";
@@ -0,0 +1,84 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type SessionStatus = "working" | "idle" | "waiting";
+
+/** A Claude Code session registered by the Vocal hooks. */
+export interface SessionInfo {
+  sessionId: string;
+  terminalPid: string;
+  termSession: string;
+  itermSession: string;
+  tmux: string;
+  tmuxPane: string;
+  cwd: string;
+  transcriptPath: string;
+  status: SessionStatus;
+  /** Unix timestamps in seconds */
+  startedAt: number;
+  lastActivity: number;
+}
+
+export interface TokenUsage {
+  inputTokens: number;
+  outputTokens: number;
+  cacheCreationInputTokens: number;
+  cacheReadInputTokens: number;
+}
+
+/** What Claude did for the last prompt of a session. */
+export interface TurnSummary {
+  prompt: string | null;
+  response: string | null;
+  /** Calls per tool name */
+  toolsUsed: Record<string, number>;
+  failedToolCalls: number;
+  filesChanged: string[];
+  usage: TokenUsage;
+}
+
+export class SessionService {
+  private static instance: SessionService;
+
+  static getInstance(): SessionService {
+    if (!SessionService.instance) {
+      SessionService.instance = new SessionService();
+    }
+    return SessionService.instance;
+  }
+
+  /** Most recently active first; sessions idle for over 12 hours are dropped. */
+  async list(): Promise<SessionInfo[]> {
+    return invoke<SessionInfo[]>("list_sessions");
+  }
+
+  /** Null when the hooks haven't recorded the session's transcript. */
+  async summary(sessionId: string): Promise<TurnSummary | null> {
+    return invoke<TurnSummary | null>("get_session_summary", { sessionId });
+  }
+
+  /** The session picked in the UI, or null for automatic routing. */
+  async getTarget(): Promise<string | null> {
+    return invoke<string | null>("get_target_session");
+  }
+
+  async setTarget(sessionId: string | null): Promise<void> {
+    return invoke<void>("set_target_session", { sessionId });
+  }
+
+  /**
+   * Sends a prompt to a Claude Code session. Without `target`, a spoken
+   * prefix such as "in the backend project, ..." in the prompt or the raw
+   * transcript routes it, then the selected session, then the session that
+   * most recently stopped. Resolves to the session it was sent to.
+   */
+  async injectPrompt(prompt: string, transcript?: string, target?: string): Promise<SessionInfo | null> {
+    return invoke<SessionInfo | null>("inject_prompt_to_claude_session", { prompt, target, transcript });
+  }
+}
+
+/** The name shown for a session: its project directory. */
+export function sessionLabel(session: SessionInfo): string {
+  return session.cwd.split("/").filter(Boolean).pop() ?? session.sessionId;
+}
+
+export const sessionService = SessionService.getInstance();